@@ -0,0 +1,88 @@
+//! Measures how many attempts per second the regroup solver makes, and how
+//! many allocations it makes per attempt, on standard instances. Each instance
+//! has one round more than any schedule can have, so every run uses up its
+//! attempts and the rates compare like with like.
+//!
+//! Run with `cargo bench`. Arguments not starting with - only run the
+//! instances whose names contain them.
+
+use socialx::solver::*;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::env;
+use std::sync::atomic::{self, AtomicUsize};
+use std::time::Instant;
+
+/// An allocator that counts the allocations made, to catch the search loop
+/// allocating again.
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, atomic::Ordering::Relaxed);
+        System.alloc(layout)
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// Name, participants, groups, rounds and attempts of each instance.
+const INSTANCES: [(&str, usize, usize, usize, usize); 4] = [
+    ("schoolgirls", 15,  5,  8, 20_000),
+    ("golf",        32,  8, 11,  2_000),
+    ("conference",  70, 10, 12,    200),
+    ("quads",       64, 16, 22,    200),
+];
+
+/// Name, placement order, restart strategy and regroup choice of each solver
+/// configuration.
+const CONFIGS: [(&str, Placement, Restart, RegroupChoice); 2] = [
+    ("default", Placement::Random, Restart::Full, RegroupChoice::Random),
+    ("tuned",   Placement::MostConstrained, Restart::Partial,
+                RegroupChoice::Conflict),
+];
+
+fn main() {
+    let filters = env::args().skip(1)
+                             .filter(|arg| !arg.starts_with('-'))
+                             .collect::<Vec<_>>();
+
+    println!("{:<12} {:<8} {:>10} {:>9} {:>12} {:>14}",
+             "instance", "solver", "attempts", "seconds", "attempts/s",
+             "allocs/attempt");
+
+    for &(name, n_parts, n_groups, n_rounds, n_attempts) in &INSTANCES {
+        if !filters.is_empty() && !filters.iter().any(|f| name.contains(f)) {
+            continue;
+        }
+        let problem = Problem { num_participants : n_parts,
+                                num_groups       : n_groups,
+                                num_rounds       : n_rounds,
+                                num_attempts     : n_attempts };
+
+        for &(config, placement, restart, choice) in &CONFIGS {
+            let mut solver = Solver::new(problem);
+            solver.set_placement(placement);
+            solver.set_restart(restart);
+            solver.set_regroup_choice(choice);
+
+            let allocations = ALLOCATIONS.load(atomic::Ordering::Relaxed);
+            let started     = Instant::now();
+            let result      = solver.solve();
+            let secs        = started.elapsed().as_secs_f64();
+            let allocations = ALLOCATIONS.load(atomic::Ordering::Relaxed)
+                            - allocations;
+
+            println!("{:<12} {:<8} {:>10} {:>9.3} {:>12.0} {:>14.3}",
+                     name, config, result.attempts, secs,
+                     result.attempts as f64 / secs,
+                     allocations as f64 / result.attempts as f64);
+        }
+    }
+}
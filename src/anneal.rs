@@ -0,0 +1,149 @@
+
+use crate::assignment::*;
+use crate::rng;
+use crate::schedule::*;
+use crate::solver::*;
+
+use rand::prelude::*;
+
+/// The temperature each run starts at. Most of the progress on hard instances
+/// is made in the narrow range between the start and end temperatures.
+const START_TEMP : f64 = 0.3;
+/// The temperature each run ends at.
+const END_TEMP   : f64 = 0.2;
+/// The factor the temperature is multiplied by at each step of the cooling.
+const COOLING    : f64 = 0.9995;
+/// The number of moves per participant and round made at each temperature.
+const MOVES      : usize = 10;
+/// The tries at finding a conflicted participant before settling for any.
+const PICKS      : usize = 8;
+
+/// The simulated annealing solver.
+/// Unlike `Solver`, which builds rounds up one participant at a time, the
+/// annealer keeps every participant in a group in every round and swaps pairs
+/// of participants between groups of a round. Each swap changes the number of
+/// repeated meetings, the cost, and swaps that lower it are always accepted,
+/// while swaps that raise it are accepted with a probability that shrinks as
+/// the temperature cools. An attempt is one complete cooling run from a fresh
+/// random assignment.
+pub struct Annealer {
+    assign   : Assignment,
+    attempts : usize,
+    cancel   : CancelToken,
+}
+impl Annealer {
+    /// Creates an Annealer for the problem. The problem is expected to have
+    /// been validated.
+    pub fn new(problem: Problem) -> Self {
+        Annealer { assign: Assignment::new(problem), attempts: 0,
+                   cancel: CancelToken::new() }
+    }
+    /// Makes the searches stop once 'cancel' is cancelled.
+    pub fn set_cancel(&mut self, cancel: CancelToken) {
+        self.cancel = cancel;
+    }
+    /// Returns the total number of attempts made so far.
+    pub fn attempts(&self) -> usize {
+        self.attempts
+    }
+    /// Searches until the problem is solved or the attempts run out.
+    pub fn solve(&mut self) -> SolveResult {
+        self.solve_with(|_| {})
+    }
+    /// Searches like `solve()`, calling 'on_best' each time the search finds
+    /// a schedule with more placements than any before it. Since the annealer
+    /// works on complete assignments, the schedules passed to 'on_best' have
+    /// the participants behind repeated meetings left out.
+    /// Problems with a bye, which only the round robin construction can
+    /// schedule, are returned unsolved at once.
+    pub fn solve_with<F>(&mut self, mut on_best: F) -> SolveResult
+    where
+        F: FnMut(&Schedule),
+    {
+        let     start     = self.attempts;
+        let mut best      = self.assign.valid_schedule();
+        let mut best_cost = u32::MAX;
+        let mut rng       = rng::fork();
+
+        if self.assign.problem.has_bye() {
+            return SolveResult { solved   : false,
+                                 attempts : 0,
+                                 best,
+                                 stats    : None };
+        }
+
+        while self.attempts < self.assign.problem.num_attempts
+            && !self.cancel.is_cancelled() {
+
+            self.attempts += 1;
+            self.assign.randomize(&mut rng);
+            if self.assign.improve(&mut best) {
+                on_best(&best);
+            }
+            let mut cost = self.assign.cost();
+            if cost > 0 && !self.assign.has_moves() {
+                // Every deal is the same, and there's no swap to make.
+                break;
+            }
+            let mut temp = START_TEMP;
+            let     n_moves = MOVES * self.assign.problem.num_participants
+                                    * self.assign.problem.num_rounds;
+
+            while temp > END_TEMP && cost > 0 && !self.cancel.is_cancelled() {
+                for _ in 0..n_moves {
+                    let (r, p, q) = self.pick_move(&mut rng);
+                    let delta     = self.assign.swap_delta(r, p, q);
+
+                    if delta <= 0 || rng.gen::<f64>()
+                                     < (-delta as f64 / temp).exp() {
+                        self.assign.swap(r, p, q);
+                        cost = (cost as i32 + delta) as u32;
+
+                        if cost < best_cost {
+                            best_cost = cost;
+                            if self.assign.improve(&mut best) {
+                                on_best(&best);
+                            }
+                        }
+                        if cost == 0 { break; }
+                    }
+                }
+                temp *= COOLING;
+            }
+            if cost == 0 {
+                return SolveResult { solved   : true,
+                                     attempts : self.attempts - start,
+                                     best,
+                                     stats    : None };
+            }
+            // Each run starts over, so its costs are compared afresh.
+            best_cost = u32::MAX;
+        }
+        SolveResult { solved   : false,
+                      attempts : self.attempts - start,
+                      best,
+                      stats    : None }
+    }
+    /// Picks a round and two participants in different groups of the round.
+    /// The first participant is preferably one with a conflict in the round.
+    fn pick_move<R: Rng>(&self, rng: &mut R) -> (usize, usize, usize) {
+        let n_parts  = self.assign.problem.num_participants;
+        let n_rounds = self.assign.problem.num_rounds;
+        let mut r    = rng.gen_range(0, n_rounds);
+        let mut p    = rng.gen_range(0, n_parts);
+
+        for _ in 0..PICKS {
+            if self.assign.is_conflicted(r, p) { break; }
+            r = rng.gen_range(0, n_rounds);
+            p = rng.gen_range(0, n_parts);
+        }
+        loop {
+            let q = rng.gen_range(0, n_parts);
+            if self.assign.group_of[r][q] != self.assign.group_of[r][p] {
+                return (r, p, q);
+            }
+        }
+    }
+}
+
+
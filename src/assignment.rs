@@ -0,0 +1,152 @@
+
+use crate::participant::*;
+use crate::participant_set::*;
+use crate::schedule::*;
+use crate::solver::*;
+
+use rand::prelude::*;
+
+/// A complete assignment of the participants to groups in every round.
+/// This is the state the local search solvers work on. Each group is kept as a
+/// ParticipantSet, so the cost of a move can be found with a few unions and
+/// popcounts.
+pub (crate) struct Assignment {
+    pub (crate) problem  : Problem,
+    pub (crate) sets     : Vec<Vec<ParticipantSet>>,
+    pub (crate) group_of : Vec<Vec<usize>>,
+}
+impl Assignment {
+    /// Creates an Assignment for the problem with every group empty.
+    pub (crate) fn new(problem: Problem) -> Self {
+        let sets     = vec![vec![ParticipantSet::new(); problem.num_groups];
+                            problem.num_rounds];
+        let group_of = vec![vec![0; problem.num_participants];
+                            problem.num_rounds];
+        Assignment { problem, sets, group_of }
+    }
+    /// Deals the participants out into the groups of each round at random.
+    pub (crate) fn randomize<R: Rng>(&mut self, rng: &mut R) {
+        let size    = self.problem.group_size();
+        let mut ord = (0..self.problem.num_participants).collect::<Vec<_>>();
+
+        for r in 0..self.problem.num_rounds {
+            ord.shuffle(rng);
+            for set in &mut self.sets[r] {
+                set.clear();
+            }
+            for (i, &p) in ord.iter().enumerate() {
+                self.sets[r][i / size].add(HParticipant { idx: p });
+                self.group_of[r][p] = i / size;
+            }
+        }
+    }
+    /// Indicates whether there are swaps to make, which there aren't with a
+    /// single group per round.
+    pub (crate) fn has_moves(&self) -> bool {
+        self.problem.num_groups > 1
+    }
+    /// Returns the set of the group 'p' is in during round 'r'.
+    #[inline]
+    pub (crate) fn group_set(&self, r: usize, p: usize) -> &ParticipantSet {
+        &self.sets[r][self.group_of[r][p]]
+    }
+    /// Returns the cost: the number of times pairs meet again after their
+    /// first meeting. It's 0 only for a solution.
+    pub (crate) fn cost(&self) -> u32 {
+        let mut total = 0;
+        for p in 0..self.problem.num_participants {
+            let mut met = ParticipantSet::new();
+            for r in 0..self.problem.num_rounds {
+                let mut others = *self.group_set(r, p);
+                others.remove(HParticipant { idx: p });
+                total += others.num_common(&met);
+                met.add_set(&others);
+            }
+        }
+        total / 2
+    }
+    /// Returns the number of members of 'set' that 'p' meets in the rounds
+    /// other than 'r'.
+    #[inline]
+    pub (crate) fn num_met(&self, p: usize, set: &ParticipantSet, r: usize) -> i32 {
+        let mut met = ParticipantSet::new();
+        for ro in 0..self.problem.num_rounds {
+            if ro != r {
+                met.add_set(self.group_set(ro, p));
+            }
+        }
+        met.num_common(set) as i32
+    }
+    /// Returns the change in cost from swapping 'p' and 'q' between their
+    /// groups in round 'r'.
+    pub (crate) fn swap_delta(&self, r: usize, p: usize, q: usize) -> i32 {
+        let mut a = *self.group_set(r, p);
+        let mut b = *self.group_set(r, q);
+        a.remove(HParticipant { idx: p });
+        b.remove(HParticipant { idx: q });
+
+        self.num_met(p, &b, r) - self.num_met(p, &a, r)
+            + self.num_met(q, &a, r) - self.num_met(q, &b, r)
+    }
+    /// Swaps 'p' and 'q' between their groups in round 'r'.
+    pub (crate) fn swap(&mut self, r: usize, p: usize, q: usize) {
+        let (hp, hq) = (HParticipant { idx: p }, HParticipant { idx: q });
+        let  gp      = self.group_of[r][p];
+        let  gq      = self.group_of[r][q];
+        self.sets[r][gp].remove(hp);
+        self.sets[r][gp].add(hq);
+        self.sets[r][gq].remove(hq);
+        self.sets[r][gq].add(hp);
+        self.group_of[r][p] = gq;
+        self.group_of[r][q] = gp;
+    }
+    /// Indicates whether 'p' meets someone in round 'r' it meets in another
+    /// round.
+    pub (crate) fn is_conflicted(&self, r: usize, p: usize) -> bool {
+        let mut others = *self.group_set(r, p);
+        others.remove(HParticipant { idx: p });
+        self.num_met(p, &others, r) > 0
+    }
+    /// Returns the current assignment as a valid, possibly partial, schedule.
+    /// Rounds are taken in order, and a participant is left out of a group if
+    /// it has met one of the members already kept in the group.
+    pub (crate) fn valid_schedule(&self) -> Schedule {
+        let n_parts  = self.problem.num_participants;
+        let mut acqs = vec![ParticipantSet::new(); n_parts];
+        let mut rvec = vec![];
+
+        for round in &self.sets {
+            let mut gvec = vec![];
+            for set in round {
+                let mut kept = ParticipantSet::new();
+                for hp in set.iter() {
+                    if !acqs[hp.idx].has_common(&kept) {
+                        kept.add(hp);
+                    }
+                }
+                for hp in kept.iter() {
+                    acqs[hp.idx].add_set(&kept);
+                }
+                let mut members = kept.iter().map(|hp| hp.idx)
+                                             .collect::<Vec<_>>();
+                members.sort_unstable();
+                gvec.push(members);
+            }
+            rvec.push(gvec);
+        }
+        Schedule::new(n_parts, self.problem.group_size(), rvec)
+    }
+    /// Replaces 'best' with `valid_schedule()` if it has more placements, and
+    /// returns whether it did.
+    pub (crate) fn improve(&self, best: &mut Schedule) -> bool {
+        let schedule = self.valid_schedule();
+        if schedule.num_placements() > best.num_placements() {
+            *best = schedule;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+
@@ -0,0 +1,107 @@
+
+use crate::rng;
+use crate::solver::*;
+
+use std::time::{Duration, Instant};
+
+/// An instance of the benchmark catalog.
+#[derive(Copy, Clone, Debug)]
+pub struct Instance {
+    /// The name the instance is picked by.
+    pub name    : &'static str,
+    /// The problem, with the attempts each run gets.
+    pub problem : Problem,
+}
+
+const fn instance(name       : &'static str,
+                  n_parts    : usize,
+                  n_groups   : usize,
+                  n_rounds   : usize,
+                  n_attempts : usize      ) -> Instance {
+    Instance { name,
+               problem: Problem { num_participants : n_parts,
+                                  num_groups       : n_groups,
+                                  num_rounds       : n_rounds,
+                                  num_attempts     : n_attempts } }
+}
+
+/// The standard instances, from easy to hard. The attempts suit the default
+/// regroup algorithm and are small enough that a run of the hard instances
+/// takes seconds rather than hours, so those are often left unsolved.
+pub const CATALOG: [Instance; 11] = [
+    instance("nine",         9,  3,  4,   100_000),
+    instance("sixteen",     16,  4,  5,   100_000),
+    instance("kirkman",     15,  5,  7, 1_000_000),
+    instance("conference",  70, 10,  5,   100_000),
+    instance("golf32-6",    32,  8,  6,    10_000),
+    instance("golf32-7",    32,  8,  7,    10_000),
+    instance("golf32-8",    32,  8,  8,     2_000),
+    instance("golf32-9",    32,  8,  9,     2_000),
+    instance("golf32-10",   32,  8, 10,     2_000),
+    instance("quads",       64, 16, 13,     1_000),
+    instance("triples21",   21,  7, 10,    10_000),
+];
+
+/// The outcome of running an instance many times.
+#[derive(Clone, Debug)]
+pub struct BenchResult {
+    /// The instance run.
+    pub instance : Instance,
+    /// The number of runs.
+    pub runs     : usize,
+    /// The times the solved runs took, shortest first.
+    pub times    : Vec<Duration>,
+}
+impl BenchResult {
+    /// Returns the number of runs that found a schedule.
+    pub fn solved(&self) -> usize {
+        self.times.len()
+    }
+    /// Returns the fraction of the runs that found a schedule.
+    pub fn success_rate(&self) -> f64 {
+        if self.runs == 0 {
+            0.0
+        } else {
+            self.solved() as f64 / self.runs as f64
+        }
+    }
+    /// Returns the median time to solve, or None if no run found a schedule.
+    pub fn median(&self) -> Option<Duration> {
+        self.percentile(50)
+    }
+    /// Returns the time within which 95% of the solved runs found a schedule,
+    /// or None if none did.
+    pub fn p95(&self) -> Option<Duration> {
+        self.percentile(95)
+    }
+    /// Returns the 'p'th percentile, by the nearest rank, of the times to
+    /// solve.
+    fn percentile(&self, p: usize) -> Option<Duration> {
+        let n = self.times.len();
+        if n == 0 {
+            return None;
+        }
+        let rank = (p * n).div_ceil(100).max(1);
+        Some(self.times[rank - 1])
+    }
+}
+
+/// Runs 'solve' on the instance 'runs' times. Run i is seeded with
+/// 'seed' + i, so the results can be repeated, and is timed.
+pub fn run<F>(instance: &Instance, runs: usize, seed: u64, mut solve: F)
+    -> BenchResult
+where
+    F: FnMut(Problem) -> SolveResult,
+{
+    let mut times = vec![];
+    for i in 0..runs {
+        rng::seed(seed.wrapping_add(i as u64));
+        let started = Instant::now();
+        let result  = solve(instance.problem);
+        if result.solved {
+            times.push(started.elapsed());
+        }
+    }
+    times.sort();
+    BenchResult { instance: *instance, runs, times }
+}
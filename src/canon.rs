@@ -0,0 +1,265 @@
+
+use crate::schedule::*;
+
+/// The relabeled rounds of a schedule with the groups and rounds sorted.
+type Certificate = Vec<Vec<Vec<usize>>>;
+
+/// The canonical form of a Schedule.
+/// Two schedules have equal canonical forms exactly when one can be turned 
+/// into the other by reordering rounds, reordering the groups within rounds, 
+/// and relabeling participants. The form can be hashed, which makes it
+/// suitable for removing duplicates from a collection of schedules, or for 
+/// looking up a schedule among published designs.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CanonicalSchedule {
+    num_participants : usize,
+    group_size       : usize,
+    rounds           : Certificate,
+}
+impl CanonicalSchedule {
+    /// Computes the canonical form of the schedule.
+    pub fn new(schedule: &Schedule) -> Self {
+        CanonicalSchedule { num_participants : schedule.num_participants(),
+                            group_size       : schedule.group_size(),
+                            rounds           : certificate(schedule) }
+    }
+    /// Returns the canonical representative as a Schedule. The rounds and
+    /// groups are sorted, and participants are labeled in canonical order.
+    pub fn to_schedule(&self) -> Schedule {
+        Schedule::new(self.num_participants, 
+                      self.group_size, 
+                      self.rounds.clone())
+    }
+}
+
+/// Computes the certificate of a Schedule.
+///
+/// The schedule is treated as a colored graph with a vertex for each
+/// participant, group and round. Colors are refined until stable, then
+/// participants are individualized one at a time, branching over each
+/// candidate, until every participant has its own color. Each such leaf
+/// yields a labeling, and the smallest relabeled schedule among the leaves is
+/// the certificate. Leaves that tie reveal automorphisms which are used to
+/// skip branches that would only repeat work.
+fn certificate(schedule: &Schedule) -> Certificate {
+    let mut search = Search::new(schedule);
+    let     colors = search.initial_colors();
+    let mut fixed  = vec![];
+    search.search(colors, &mut fixed);
+    search.best.unwrap_or_default()
+}
+
+/// The state of the search for the canonical labeling.
+struct Search<'a> {
+    schedule  : &'a Schedule,
+    n_parts   : usize,
+    adjacent  : Vec<Vec<usize>>,
+    best      : Option<Certificate>,
+    best_lab  : Vec<usize>,
+    first     : Option<Certificate>,
+    first_lab : Vec<usize>,
+    autos     : Vec<Vec<usize>>,
+}
+impl<'a> Search<'a> {
+    /// Builds the graph of participants, groups and rounds.
+    fn new(schedule: &'a Schedule) -> Self {
+        let n_parts  = schedule.num_participants();
+        let n_groups = schedule.rounds().iter().map(|r| r.len())
+                                               .sum::<usize>();
+        let n_rounds = schedule.num_rounds();
+        let mut adjacent = vec![vec![]; n_parts + n_groups + n_rounds];
+        let mut vg       = n_parts;
+        for (round_i, round) in schedule.rounds().iter().enumerate() {
+            let vr = n_parts + n_groups + round_i;
+            for group in round {
+                for &p in group {
+                    adjacent[p] .push(vg);
+                    adjacent[vg].push(p);
+                }
+                adjacent[vg].push(vr);
+                adjacent[vr].push(vg);
+                vg += 1;
+            }
+        }
+        Search { schedule, n_parts, adjacent,
+                 best  : None, best_lab  : vec![],
+                 first : None, first_lab : vec![],
+                 autos : vec![] }
+    }
+    /// Colors vertices by kind: participants, then groups, then rounds.
+    fn initial_colors(&self) -> Vec<u32> {
+        let n_groups = self.adjacent.len() - self.n_parts
+                                           - self.schedule.num_rounds();
+        (0..self.adjacent.len()).map(|v| {
+            if v < self.n_parts { 0 }
+            else if v < self.n_parts + n_groups { 1 }
+            else { 2 }
+        }).collect()
+    }
+    /// Splits color classes by the colors of their neighbours until no class
+    /// splits further. The new colors are ranks of sorted signatures, so they
+    /// don't depend on how vertices happen to be numbered.
+    fn refine(&self, mut colors: Vec<u32>) -> Vec<u32> {
+        let mut n_colors = count_distinct(&colors);
+        loop {
+            let sigs = (0..colors.len()).map(|v| {
+                let mut nc = self.adjacent[v].iter()
+                                             .map(|&u| colors[u])
+                                             .collect::<Vec<_>>();
+                nc.sort_unstable();
+                (colors[v], nc)
+            }).collect::<Vec<_>>();
+
+            let mut ranked = sigs.clone();
+            ranked.sort();
+            ranked.dedup();
+
+            for (v, sig) in sigs.iter().enumerate() {
+                colors[v] = ranked.binary_search(sig).unwrap() as u32;
+            }
+            if ranked.len() == n_colors {
+                return colors;
+            }
+            n_colors = ranked.len();
+        }
+    }
+    /// Explores the subtree below the partition given by 'colors'. 'fixed'
+    /// holds the participants individualized on the way down.
+    fn search(&mut self, colors: Vec<u32>, fixed: &mut Vec<usize>) {
+        let colors = self.refine(colors);
+
+        // The target cell is the participant color class with the smallest
+        // color that still has more than one member.
+        let mut counts = vec![0; colors.len()];
+        for &c in &colors[..self.n_parts] {
+            counts[c as usize] += 1;
+        }
+        let target = (0..counts.len()).find(|&c| counts[c] > 1);
+
+        let target = match target {
+            Some(c) => c as u32,
+            None    => {
+                self.leaf(&colors);
+                return;
+            },
+        };
+        let cell = (0..self.n_parts).filter(|&p| colors[p] == target)
+                                    .collect::<Vec<_>>();
+        let mut tried: Vec<usize> = vec![];
+
+        for &p in &cell {
+            if !tried.is_empty() {
+                let orbits = self.orbits(fixed);
+                if tried.iter().any(|&t| find(&orbits, t) == find(&orbits, p)) {
+                    continue;
+                }
+            }
+            let mut child = colors.iter().map(|&c| c * 2 + 1)
+                                         .collect::<Vec<_>>();
+            child[p] -= 1;
+
+            fixed.push(p);
+            self.search(child, fixed);
+            fixed.pop();
+            tried.push(p);
+        }
+    }
+    /// Records the labeling at a leaf of the search tree.
+    fn leaf(&mut self, colors: &[u32]) {
+        let mut order = (0..self.n_parts).collect::<Vec<_>>();
+        order.sort_by_key(|&p| colors[p]);
+        let mut labels = vec![0; self.n_parts];
+        for (label, &p) in order.iter().enumerate() {
+            labels[p] = label;
+        }
+        let cert = relabel(self.schedule, &labels);
+
+        match &self.first {
+            None => {
+                self.first     = Some(cert.clone());
+                self.first_lab = labels.clone();
+            },
+            Some(first) if *first == cert => {
+                let auto = automorphism(&labels, &self.first_lab);
+                self.autos.push(auto);
+                return;
+            },
+            _ => {},
+        }
+        match &self.best {
+            Some(best) if *best < cert => {},
+            Some(best) if *best == cert => {
+                let auto = automorphism(&labels, &self.best_lab);
+                self.autos.push(auto);
+            },
+            _ => {
+                self.best     = Some(cert);
+                self.best_lab = labels;
+            },
+        }
+    }
+    /// Returns the orbits, as a union-find table, of the automorphisms found
+    /// so far that leave every participant in 'fixed' in place.
+    fn orbits(&self, fixed: &[usize]) -> Vec<usize> {
+        let mut parent = (0..self.n_parts).collect::<Vec<_>>();
+        for auto in &self.autos {
+            if fixed.iter().any(|&p| auto[p] != p) {
+                continue;
+            }
+            for (p, &q) in auto.iter().enumerate() {
+                let a = find(&parent, p);
+                let b = find(&parent, q);
+                if a != b {
+                    parent[a.max(b)] = a.min(b);
+                }
+            }
+        }
+        parent
+    }
+}
+
+/// Applies 'labels' to the participants of the schedule and sorts the members,
+/// groups and rounds.
+fn relabel(schedule: &Schedule, labels: &[usize]) -> Certificate {
+    let mut rounds = schedule.rounds().iter().map(|round| {
+        let mut groups = round.iter().map(|group| {
+            let mut members = group.iter()
+                                   .map(|&p| labels[p])
+                                   .collect::<Vec<_>>();
+            members.sort_unstable();
+            members
+        }).collect::<Vec<_>>();
+        groups.sort();
+        groups
+    }).collect::<Vec<_>>();
+    rounds.sort();
+    rounds
+}
+
+/// Returns the permutation taking each participant to the participant with the
+/// same label in the other labeling.
+fn automorphism(labels: &[usize], other_labels: &[usize]) -> Vec<usize> {
+    let mut inverse = vec![0; other_labels.len()];
+    for (p, &label) in other_labels.iter().enumerate() {
+        inverse[label] = p;
+    }
+    labels.iter().map(|&label| inverse[label]).collect()
+}
+
+/// Finds the representative of 'p' in a union-find table.
+fn find(parent: &[usize], mut p: usize) -> usize {
+    while parent[p] != p {
+        p = parent[p];
+    }
+    p
+}
+
+/// Returns the number of distinct values.
+fn count_distinct(colors: &[u32]) -> usize {
+    let mut sorted = colors.to_vec();
+    sorted.sort_unstable();
+    sorted.dedup();
+    sorted.len()
+}
+
+
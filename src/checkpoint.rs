@@ -0,0 +1,126 @@
+use std::fmt;
+use std::str::FromStr;
+use crate::schedule::*;
+use crate::solver::*;
+
+/// The first line of a checkpoint.
+const HEADER: &str = "socialx checkpoint";
+
+/// The names of a checkpoint's schedule sections, in the order written.
+const SECTIONS: [&str; 3] = ["[start]", "[kept]", "[best]"];
+
+/// The state of a regroup search between two of its attempts, from which
+/// `Solver::from_checkpoint()` carries on as the search would have.
+/// Checkpoints are written and read as text: a header line, a `name : value`
+/// line per setting, then the schedules, each under its section name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Checkpoint {
+    pub problem   : Problem,
+    pub placement : Placement,
+    pub restart   : Restart,
+    pub choice    : RegroupChoice,
+    /// The attempts made before the checkpoint.
+    pub attempts  : usize,
+    /// The seed the generator was reseeded with when the checkpoint was taken.
+    pub seed      : u64,
+    /// How many partial restarts were made, which picks the next restart's
+    /// length.
+    pub luby      : usize,
+    /// The rounds the search was started with and never changes.
+    pub start     : Schedule,
+    /// The rounds a partial restart keeps for the next attempt.
+    pub kept      : Schedule,
+    /// The best schedule found before the checkpoint.
+    pub best      : Schedule,
+}
+impl fmt::Display for Checkpoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", HEADER)?;
+        writeln!(f, "participants   : {}", self.problem.num_participants)?;
+        writeln!(f, "groups         : {}", self.problem.num_groups)?;
+        writeln!(f, "rounds         : {}", self.problem.num_rounds)?;
+        writeln!(f, "attempts_limit : {}", self.problem.num_attempts)?;
+        writeln!(f, "attempts       : {}", self.attempts)?;
+        writeln!(f, "seed           : {}", self.seed)?;
+        writeln!(f, "placement      : {}", self.placement)?;
+        writeln!(f, "restart        : {}", self.restart)?;
+        writeln!(f, "regroup        : {}", self.choice)?;
+        writeln!(f, "luby           : {}", self.luby)?;
+        let schedules = [&self.start, &self.kept, &self.best];
+        for (name, schedule) in SECTIONS.iter().zip(&schedules) {
+            writeln!(f, "{}", name)?;
+            write!(f, "{}", schedule)?;
+        }
+        Ok(())
+    }
+}
+impl FromStr for Checkpoint {
+    type Err = String;
+
+    /// Reads a checkpoint in the layout it's written in.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines();
+        if lines.next().map(str::trim) != Some(HEADER) {
+            return Err("Not a checkpoint.".to_string());
+        }
+        let mut values   = vec![];
+        let mut sections = vec![String::new(); SECTIONS.len()];
+        let mut section  = None;
+
+        for line in lines {
+            if let Some(i) = SECTIONS.iter().position(|&n| n == line.trim()) {
+                section = Some(i);
+            } else if let Some(i) = section {
+                sections[i].push_str(line);
+                sections[i].push('\n');
+            } else if let Some((name, value)) = line.split_once(':') {
+                values.push((name.trim(), value.trim()));
+            }
+        }
+        let value = |name: &str| {
+            values.iter()
+                  .find(|(n, _)| *n == name)
+                  .map(|(_, v)| *v)
+                  .ok_or(format!("Checkpoint has no {}.", name))
+        };
+        let number = |name: &str| {
+            value(name)?.parse::<usize>()
+                        .map_err(|_| format!("Checkpoint has an invalid {}.",
+                                             name))
+        };
+        let problem = Problem { num_participants : number("participants")?,
+                                num_groups       : number("groups")?,
+                                num_rounds       : number("rounds")?,
+                                num_attempts     : number("attempts_limit")? };
+        let n_parts    = problem.num_participants;
+        let group_size = Schedule::group_size_for(n_parts,
+                                                  problem.num_groups);
+        let mut schedules = vec![];
+        for text in &sections {
+            if text.trim().is_empty() {
+                schedules.push(Schedule::new(n_parts, group_size, vec![]));
+            } else {
+                schedules.push(text.parse::<Schedule>()?
+                                   .with_parameters(n_parts, group_size));
+            }
+        }
+        let best  = schedules.pop().unwrap();
+        let kept  = schedules.pop().unwrap();
+        let start = schedules.pop().unwrap();
+
+        Ok(Checkpoint {
+            problem,
+            placement : value("placement")?.parse()?,
+            restart   : value("restart")?.parse()?,
+            choice    : value("regroup")?.parse()?,
+            attempts  : number("attempts")?,
+            seed      : value("seed")?.parse::<u64>()
+                                      .map_err(|_| "Checkpoint has an invalid \
+                                                    seed.".to_string())?,
+            luby      : number("luby")?,
+            start,
+            kept,
+            best,
+        })
+    }
+}
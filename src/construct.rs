@@ -0,0 +1,722 @@
+
+use std::fmt;
+use std::slice;
+use rand::prelude::*;
+use rand::rngs::StdRng;
+use crate::field::*;
+use crate::group::*;
+use crate::mols::*;
+use crate::participant::*;
+use crate::round::*;
+use crate::schedule::*;
+use crate::solver::*;
+
+/// The limit on the nodes each try at finding a base round may explore.
+const BASE_NODES : u64 = 5_000;
+/// The tries at finding a base round before the difference method is given
+/// up on.
+const BASE_TRIES : u64 = 10;
+/// The limit on the nodes each try at finding the base groups of a Kirkman
+/// triple system by `kirkman_developed()` may explore. Tries either succeed
+/// early or tend not to at all, so they're kept short.
+const KTS_NODES  : u64 = 2_000;
+/// The tries at finding the base groups of a Kirkman triple system before
+/// `kirkman_developed()` is given up on.
+const KTS_TRIES  : u64 = 1_000;
+
+/// Rounds of groups of participant indices, as held by Schedule.
+type Groupings = Vec<Vec<Vec<usize>>>;
+
+/// The families of designs schedules can be constructed from.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Design {
+    /// A round robin of pairs by the circle method. With an odd number of
+    /// participants, each sits out one round.
+    RoundRobin       { participants: usize },
+    /// The lines of the affine geometry AG(dimension, order), whose points
+    /// are the vectors over GF(order). Each round is a class of parallel
+    /// lines. With dimension 2 this is the affine plane of the order.
+    AffineGeometry   { order: usize, dimension: usize },
+    /// The rows, columns and symbols of a set of mutually orthogonal Latin
+    /// squares. See `Mols`.
+    LatinSquares     { order: usize, squares: usize },
+    /// A Kirkman triple system, a resolvable design in groups of 3.
+    Kirkman          { order: usize },
+    /// A resolvable design developed from a base round by the difference
+    /// method. Participants are a fixed point and group_size - 1 copies of
+    /// the integers mod 'modulus', and each round adds one to every number in
+    /// the round before it.
+    Difference       { modulus: usize, group_size: usize },
+}
+impl fmt::Display for Design {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use Design::*;
+        match self {
+            RoundRobin { participants } if participants % 2 == 1 => {
+                write!(f, "round robin by the circle method, with a rotating \
+                           bye")
+            },
+            RoundRobin { .. } => {
+                write!(f, "round robin by the circle method")
+            },
+            AffineGeometry { order, dimension: 2 } => {
+                write!(f, "affine plane of order {}", order)
+            },
+            AffineGeometry { order, dimension } => {
+                write!(f, "affine geometry AG({}, {})", dimension, order)
+            },
+            LatinSquares { order, squares: 1 } => {
+                write!(f, "Latin square of order {}", order)
+            },
+            LatinSquares { order, squares } => {
+                write!(f, "{} mutually orthogonal Latin squares of order {}",
+                       squares, order)
+            },
+            Kirkman { order } => {
+                write!(f, "Kirkman triple system of order {}", order)
+            },
+            Difference { modulus, group_size } => {
+                write!(f, "resolvable design of order {} in groups of {}, \
+                           developed mod {}", (group_size - 1) * modulus + 1,
+                                              group_size, modulus)
+            },
+        }
+    }
+}
+
+/// A schedule built directly from a known design rather than searched for.
+/// The groupings are held in the same aggregates the solver uses, with every
+/// participant acquainted with the members of their groups.
+pub struct Construction {
+    design  : Design,
+    problem : Problem,
+    parts   : Participants,
+    groups  : Groups,
+    rounds  : Rounds,
+    hrounds : Vec<HRound>,
+}
+impl Construction {
+    /// Constructs a schedule for the problem if its parameters match a design
+    /// family that supplies at least its number of rounds. Returns None if no
+    /// construction applies, in which case the schedule has to be searched for.
+    /// The problem is expected to have been validated.
+    pub fn new(problem: Problem) -> Option<Self> {
+        let (design, rvec) = round_robin(&problem)
+                                 .or_else(|| affine_geometry(&problem))
+                                 .or_else(|| latin_squares(&problem))
+                                 .or_else(|| kirkman_system(&problem))
+                                 .or_else(|| difference(&problem))?;
+        Some(Construction::from_groupings(design, problem, rvec))
+    }
+    /// Returns the design the schedule was constructed from.
+    pub fn design(&self) -> Design {
+        self.design
+    }
+    /// Returns the problem the schedule was constructed for.
+    pub fn problem(&self) -> &Problem {
+        &self.problem
+    }
+    /// Returns the participants, acquainted as the schedule groups them.
+    pub fn participants(&self) -> &Participants {
+        &self.parts
+    }
+    /// Returns the groups of every round.
+    pub fn groups(&self) -> &Groups {
+        &self.groups
+    }
+    /// Returns the rounds.
+    pub fn rounds(&self) -> &Rounds {
+        &self.rounds
+    }
+    /// Returns the handles of the rounds, in order.
+    pub fn round_handles(&self) -> &[HRound] {
+        &self.hrounds
+    }
+    /// Returns a snapshot of the groupings.
+    pub fn schedule(&self) -> Schedule {
+        Schedule::from_rounds(&self.hrounds,
+                              &self.rounds,
+                              &self.groups,
+                              self.problem.num_participants,
+                              self.problem.group_size())
+    }
+    /// Loads the first rounds of the groupings into the aggregates.
+    fn from_groupings(design: Design, problem: Problem, rvec: Groupings) 
+        -> Self 
+    {
+        let n_groups   = problem.num_groups;
+        let mut parts  = Participants::new();
+        let mut groups = Groups::new();
+        let mut rounds = Rounds::new();
+        let     hparts = parts.hcalloc(problem.num_participants);
+        let     hgs    = groups.hcalloc(n_groups * problem.num_rounds,
+                                        problem.group_size() as u32);
+        let     hrs    = rounds.hcalloc(problem.num_rounds);
+
+        for (round_i, gvec) in rvec.iter().take(problem.num_rounds)
+                                          .enumerate() {
+            let gr_start     = n_groups * round_i;
+            let hgroup_slice = &hgs[gr_start..gr_start + n_groups];
+            rounds.add_groups(hrs[round_i], hgroup_slice);
+            parts.prepare_for_new_round();
+
+            for (&hg, members) in hgroup_slice.iter().zip(gvec) {
+                for &p in members {
+                    let joined = parts.try_join(hparts[p], hg, &mut rounds,
+                                                &mut groups);
+                    debug_assert!(joined);
+                }
+            }
+        }
+        Construction { design, problem, parts, groups, rounds, hrounds: hrs }
+    }
+}
+
+/// Returns the first 'n_rounds' rounds, with the members of each group sorted,
+/// or None if there are fewer.
+fn first_rounds(mut rvec: Groupings, n_rounds: usize) -> Option<Groupings> {
+    if rvec.len() < n_rounds {
+        return None;
+    }
+    rvec.truncate(n_rounds);
+    for group in rvec.iter_mut().flatten() {
+        group.sort_unstable();
+    }
+    Some(rvec)
+}
+
+/// Returns the rounds of a round robin if the problem has groups of 2 and no
+/// more than its p - 1 rounds, or p with an odd number p of participants.
+///
+/// By the circle method, with an even number n of participants, participant
+/// n - 1 stays put while the others take turns around a circle of n - 1
+/// places. In round r, it meets r, and r - i meets r + i, mod n - 1, for
+/// 0 < i < n / 2. An odd number of participants is given a phantom one to
+/// make it even, and whoever meets the phantom sits out the round.
+fn round_robin(problem: &Problem) -> Option<(Design, Groupings)> {
+    let n_parts = problem.num_participants;
+    if problem.group_size() != 2 || n_parts < 2 {
+        return None;
+    }
+    let n    = n_parts + n_parts % 2;
+    let rvec = (0..n - 1).map(|r| {
+        let mut gvec = vec![vec![r, n - 1]];
+        for i in 1..n / 2 {
+            gvec.push(vec![(r + n - 1 - i) % (n - 1), (r + i) % (n - 1)]);
+        }
+        gvec.retain(|pair| pair.iter().all(|&p| p < n_parts));
+        gvec.sort_unstable_by_key(|pair| *pair.iter().min().unwrap());
+        gvec
+    })
+    .collect();
+    let rvec = first_rounds(rvec, problem.num_rounds)?;
+    Some((Design::RoundRobin { participants: n_parts }, rvec))
+}
+
+/// Returns the rounds of the affine geometry AG(n, q) if the problem has q^n
+/// participants in groups of q, for a prime power q and n of at least 2, and
+/// no more than the geometry's (q^n - 1) / (q - 1) rounds.
+fn affine_geometry(problem: &Problem) -> Option<(Design, Groupings)> {
+    let q       = problem.group_size();
+    let n_parts = problem.num_participants;
+    if q < 2 {
+        return None;
+    }
+    let (mut n, mut size) = (0, 1);
+    while size < n_parts {
+        size *= q;
+        n    += 1;
+    }
+    if size != n_parts || n < 2 {
+        return None;
+    }
+    let field = Field::new(q)?;
+
+    // Points are numbered by reading their coordinates as base q digits.
+    let coords = |x: usize| (0..n).map(|i| x / q.pow(i as u32) % q)
+                                  .collect::<Vec<_>>();
+    let number = |v: &[usize]| v.iter().rev().fold(0, |acc, &d| acc * q + d);
+
+    // The directions are the nonzero vectors whose last nonzero coordinate is
+    // 1, one per class of parallel lines.
+    let directions = (1..n_parts).map(coords)
+                                 .filter(|v| v.iter().rev()
+                                              .find(|&&d| d != 0) == Some(&1));
+    let mut rvec = vec![];
+    for dir in directions {
+        let mut on_line = vec![false; n_parts];
+        let mut gvec    = vec![];
+        for x in 0..n_parts {
+            if on_line[x] { continue; }
+            let start = coords(x);
+            let point = |t: usize| {
+                let pt = start.iter().zip(&dir)
+                              .map(|(&s, &d)| field.add(s, field.mul(t, d)))
+                              .collect::<Vec<_>>();
+                number(&pt)
+            };
+            let line = (0..q).map(point).collect::<Vec<_>>();
+            for &p in &line {
+                on_line[p] = true;
+            }
+            gvec.push(line);
+        }
+        rvec.push(gvec);
+    }
+    let rvec = first_rounds(rvec, problem.num_rounds)?;
+    Some((Design::AffineGeometry { order: q, dimension: n }, rvec))
+}
+
+/// Returns the rounds given by the rows, columns and squares of a set of
+/// mutually orthogonal Latin squares of order k, if the problem has k * k
+/// participants in groups of k. Prime power orders are covered by the affine
+/// planes, which these then match, so this is for the other orders.
+fn latin_squares(problem: &Problem) -> Option<(Design, Groupings)> {
+    let k = problem.group_size();
+    if k < 2 || problem.num_participants != k * k {
+        return None;
+    }
+    let mols     = Mols::new(k);
+    let schedule = mols.schedule(problem.num_rounds)?;
+    let design   = Design::LatinSquares { order   : k, 
+                                          squares : mols.squares().len() };
+    Some((design, schedule.rounds().to_vec()))
+}
+
+/// Returns the rounds of a Kirkman triple system if the problem has groups of
+/// 3 and one of the constructions in `kirkman()` applies.
+fn kirkman_system(problem: &Problem) -> Option<(Design, Groupings)> {
+    let n_parts = problem.num_participants;
+    if problem.group_size() != 3 {
+        return None;
+    }
+    let rvec = first_rounds(kirkman(n_parts)?, problem.num_rounds)?;
+    Some((Design::Kirkman { order: n_parts }, rvec))
+}
+
+/// Returns every round of a Kirkman triple system on 'v' participants, for
+/// the orders that can be reached from these constructions:
+/// - A single group is one for v = 3.
+/// - For v = 2q + 1, with q = 6t + 1 a prime power, there's the cyclotomic
+///   construction of Ray-Chaudhuri and Wilson, developed over GF(q).
+/// - For v = 3w, a system on w participants can be tripled.
+/// - For any other v = 3m, base groups are searched for, to be developed mod
+///   m by `kirkman_developed()`.
+///
+/// Between them, these reach every order up to `MAX_PARTICIPANTS`.
+fn kirkman(v: usize) -> Option<Groupings> {
+    if v == 3 {
+        return Some(vec![vec![vec![0, 1, 2]]]);
+    }
+    if v % 6 != 3 {
+        return None;
+    }
+    let q = (v - 1) / 2;
+    if q % 6 == 1 {
+        if let Some(field) = Field::new(q) {
+            if let Some(rvec) = kirkman_cyclotomic(&field) {
+                return Some(rvec);
+            }
+        }
+    }
+    kirkman(v / 3).map(|rvec| kirkman_tripled(&rvec, v / 3))
+                  .or_else(|| kirkman_developed(v / 3))
+}
+
+/// Builds a Kirkman triple system on 2q + 1 participants from GF(q), where
+/// q = 6t + 1. Element g of GF(q) is participant g in the first class and
+/// q + g in the second, and participant 2q is fixed. With w a primitive
+/// element, the base round holds
+/// - the fixed participant with 0 of each class,
+/// - the triples w^i * {1, w^2t, w^4t} of the first class, for i < t, and
+/// - the triples c, c * x and c * y, with c in the first class and the
+///   others in the second, for each c = w^(t + i + 2tj) with i < t, j < 3.
+///
+/// The base round covers each difference between the classes once when
+/// x, y, 1 - x and 1 - y are nonzero, the logarithms of x and y differ by t
+/// mod 2t, and so do those of 1 - x and 1 - y. Round g adds g to every
+/// participant of the base round but the fixed one.
+fn kirkman_cyclotomic(field: &Field) -> Option<Groupings> {
+    let q = field.order;
+    let t = (q - 1) / 6;
+
+    // Find a primitive element and tabulate its powers and logarithms.
+    let power_table = |w: usize| {
+        let mut powers = vec![1];
+        while powers.len() < q - 1 {
+            powers.push(field.mul(*powers.last().unwrap(), w));
+        }
+        powers
+    };
+    let powers = (2..q).map(power_table)
+                       .find(|powers| !powers[1..].contains(&1))?;
+    let mut log = vec![0; q];
+    for (e, &x) in powers.iter().enumerate() {
+        log[x] = e;
+    }
+    let one_minus = |x: usize| field.add(1, field.neg(x));
+    let apart     = |a: usize, b: usize| (log[b] + 2 * t - log[a] % (2 * t))
+                                         % (2 * t) == t;
+    let (x, y) = (2..q).flat_map(|x| (2..q).map(move |y| (x, y)))
+                       .find(|&(x, y)| {
+                           x != y && apart(x, y)
+                                  && apart(one_minus(x), one_minus(y))
+                       })?;
+    let mut base = vec![vec![0, q, 2 * q]];
+    for i in 0..t {
+        base.push(vec![powers[i], powers[i + 2 * t], powers[i + 4 * t]]);
+    }
+    for i in 0..t {
+        for j in 0..3 {
+            let c = powers[t + i + 2 * t * j];
+            base.push(vec![c, q + field.mul(c, x), q + field.mul(c, y)]);
+        }
+    }
+    let rvec = (0..q).map(|g| {
+        base.iter().map(|group| {
+            group.iter().map(|&p| {
+                if p == 2 * q {
+                    p
+                } else {
+                    p / q * q + field.add(p % q, g)
+                }
+            })
+            .collect()
+        })
+        .collect()
+    })
+    .collect();
+    Some(rvec)
+}
+
+/// Triples a Kirkman triple system on 'w' participants into one on 3w.
+/// Participant x of copy a is numbered a * w + x. The first round groups the
+/// three copies of each participant. Each group {x, y, z} of an old round
+/// then gives the groups {(x, a), (y, a + s), (z, a - s)}, mod 3, of the new
+/// round s of the three made from the old one.
+fn kirkman_tripled(rvec: &[Vec<Vec<usize>>], w: usize) -> Groupings {
+    let mut tripled = vec![(0..w).map(|x| vec![x, w + x, 2 * w + x])
+                                 .collect::<Vec<_>>()];
+    for round in rvec {
+        for s in 0..3 {
+            let mut gvec = vec![];
+            for group in round {
+                let (x, y, z) = (group[0], group[1], group[2]);
+                for a in 0..3 {
+                    gvec.push(vec![a * w + x,
+                                   (a + s) % 3 * w + y,
+                                   (a + 3 - s) % 3 * w + z]);
+                }
+            }
+            tripled.push(gvec);
+        }
+    }
+    tripled
+}
+
+/// Builds a Kirkman triple system on 3m participants, for an odd m, by the
+/// difference method, if its base groups can be found. Participant (x, c),
+/// of x mod m and a class c < 3, is numbered c * m + x, and adding g to every
+/// x maps the system onto itself.
+///
+/// There are two kinds of base groups. The (m - 1) / 2 short ones each take
+/// a participant of every class, so the m groups made by adding 0 to m - 1
+/// to them make up a round of their own. The others make up a base round,
+/// and the m rounds made by adding 0 to m - 1 to it are the rest. As with
+/// `difference()`, every pair meets exactly once when the base groups hold
+/// each difference between each ordered pair of classes exactly once, and
+/// they're found by a depth first search, restarted with the next seed when
+/// it runs too long.
+fn kirkman_developed(m: usize) -> Option<Groupings> {
+    let base = (0..KTS_TRIES).map(|seed| BaseSearch::new(m, 3, 3, KTS_NODES,
+                                                         seed))
+                             .find_map(|mut base| {
+                                 if base.search_short((m - 1) / 2) {
+                                     Some(base)
+                                 } else {
+                                     None
+                                 }
+                             })?;
+    let develop = |groups: &[Vec<usize>], g: usize| {
+        groups.iter().map(|group| {
+            group.iter().map(|&p| p / m * m + (p % m + g) % m)
+                        .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>()
+    };
+    let mut rvec = (0..m).map(|g| develop(&base.groups, g))
+                         .collect::<Vec<_>>();
+    for group in &base.short {
+        rvec.push((0..m).flat_map(|g| develop(slice::from_ref(group), g))
+                        .collect());
+    }
+    Some(rvec)
+}
+
+/// Returns the rounds of a resolvable design developed by the difference
+/// method, if the problem's parameters are those of a resolvable design with
+/// groups of 3 or more and a base round can be found.
+///
+/// With groups of k, R = (v - 1) / (k - 1) rounds and v participants, the
+/// participants other than the last are the pairs (x, c) of x mod R and a
+/// class c < k - 1, and the last, infinity, is left fixed. Round i is the base
+/// round with i added to each x. Every pair meets exactly once when the base
+/// round's groups hold each difference x' - x, between each ordered pair of
+/// classes (c, c'), exactly once, leaving out the difference 0 within a class.
+/// The group of infinity is taken to be it and (0, c) for every class, and the
+/// other groups are found by a depth first search. Its choices are tried in a
+/// random order, from a fixed seed so a problem always gets the same schedule,
+/// and it's restarted with the next seed when it runs too long.
+fn difference(problem: &Problem) -> Option<(Design, Groupings)> {
+    let k       = problem.group_size();
+    let n_parts = problem.num_participants;
+    if k < 3 || problem.num_groups < 2 
+             || !(n_parts - 1).is_multiple_of(k - 1) {
+        return None;
+    }
+    // With an even modulus, the pair x and x + modulus / 2 of a class would
+    // meet twice.
+    let modulus = (n_parts - 1) / (k - 1);
+    if problem.num_rounds > modulus || modulus.is_multiple_of(2) {
+        return None;
+    }
+    let base = (0..BASE_TRIES).map(|seed| BaseSearch::new(modulus, k, k - 1,
+                                                          BASE_NODES, seed))
+                              .find_map(|mut base| {
+                                  if base.search() { Some(base) } else { None }
+                              })?;
+    let infinity = n_parts - 1;
+    let rvec = (0..modulus).map(|i| {
+        base.groups.iter().map(|group| {
+            group.iter().map(|&p| {
+                if p == infinity {
+                    p
+                } else {
+                    p / modulus * modulus + (p % modulus + i) % modulus
+                }
+            })
+            .collect()
+        })
+        .collect()
+    })
+    .collect();
+    let rvec = first_rounds(rvec, problem.num_rounds)?;
+    Some((Design::Difference { modulus, group_size: k }, rvec))
+}
+
+/// The search for the base round of a design developed by the difference
+/// method. Participant (x, c) is numbered c * modulus + x.
+struct BaseSearch {
+    modulus   : usize,
+    size      : usize,
+    n_classes : usize,
+    groups    : Vec<Vec<usize>>,
+    /// The short groups of `search_short()`.
+    short     : Vec<Vec<usize>>,
+    placed    : Vec<bool>,
+    used      : Vec<bool>,
+    nodes     : u64,
+    max_nodes : u64,
+    rng       : StdRng,
+}
+impl BaseSearch {
+    fn new(modulus   : usize,
+           size      : usize,
+           n_classes : usize,
+           max_nodes : u64,
+           seed      : u64  ) -> Self {
+        BaseSearch { modulus, size, n_classes, max_nodes,
+                     groups : vec![],
+                     short  : vec![],
+                     placed : vec![false; modulus * n_classes],
+                     used   : vec![false; n_classes * n_classes * modulus],
+                     nodes  : 0,
+                     rng    : StdRng::seed_from_u64(seed) }
+    }
+    /// Returns the index of the difference from 'p' to 'q' in 'used'.
+    fn diff(&self, p: usize, q: usize) -> usize {
+        let m            = self.modulus;
+        let (cp, cq)     = (p / m, q / m);
+        let d            = (q % m + m - p % m) % m;
+        (cp * self.n_classes + cq) * m + d
+    }
+    /// Collects the differences between 'p' and the members of the group.
+    fn diffs(&self, group: &[usize], p: usize, ds: &mut Vec<usize>) {
+        ds.clear();
+        for &m in group {
+            ds.push(self.diff(m, p));
+            ds.push(self.diff(p, m));
+        }
+    }
+    /// Indicates whether 'p' can join the group without reusing a difference.
+    fn fits(&self, group: &[usize], p: usize) -> bool {
+        let mut ds = Vec::with_capacity(group.len() * 2);
+        self.diffs(group, p, &mut ds);
+        ds.iter().enumerate().all(|(i, &d)| !self.used[d] 
+                                            && !ds[..i].contains(&d))
+    }
+    /// Indicates whether 'p' and 'q' could share a group, like `fits()` for a
+    /// group of one.
+    fn fits_pair(&self, p: usize, q: usize) -> bool {
+        let (d1, d2) = (self.diff(p, q), self.diff(q, p));
+        d1 != d2 && !self.used[d1] && !self.used[d2]
+    }
+    /// Marks or clears the differences between 'p' and the members of the
+    /// group.
+    fn mark(&mut self, group: &[usize], p: usize, on: bool) {
+        let mut ds = Vec::with_capacity(group.len() * 2);
+        self.diffs(group, p, &mut ds);
+        for d in ds {
+            self.used[d] = on;
+        }
+    }
+    /// Marks the difference of 0 within each class, which can never occur.
+    fn mark_zeros(&mut self) {
+        let m = self.modulus;
+        for c in 0..self.n_classes {
+            let d = self.diff(c * m, c * m);
+            self.used[d] = true;
+        }
+    }
+    /// Searches for the base round. Returns true if one was found, in which
+    /// case it's left in 'groups'.
+    fn search(&mut self) -> bool {
+        let m = self.modulus;
+        self.mark_zeros();
+        // The group of infinity; its differences with infinity don't count.
+        let mut first = vec![];
+        for c in 0..self.n_classes {
+            let p = c * m;
+            self.mark(&first, p, true);
+            self.placed[p] = true;
+            first.push(p);
+        }
+        first.push(m * self.n_classes);
+        self.groups.push(first);
+        self.next_group()
+    }
+    /// Searches for 'n_short' short groups of 3 classes, each with the
+    /// participant 0 of the first class and one of each of the others, then
+    /// for a base round of all the participants. Returns true if they were
+    /// found, in which case they're left in 'short' and 'groups'.
+    fn search_short(&mut self, n_short: usize) -> bool {
+        self.mark_zeros();
+        self.next_short(n_short) && self.next_group()
+    }
+    /// Finds the short groups after those already in 'short'.
+    fn next_short(&mut self, n_short: usize) -> bool {
+        if self.short.len() == n_short {
+            return true;
+        }
+        let m = self.modulus;
+        let mut choices = (0..m * m).collect::<Vec<_>>();
+        choices.shuffle(&mut self.rng);
+
+        for choice in choices {
+            if self.nodes >= self.max_nodes {
+                return false;
+            }
+            self.nodes += 1;
+            let group = [0, m + choice % m, 2 * m + choice / m];
+            let mut n_marked = 0;
+            while n_marked < group.len()
+                && self.fits(&group[..n_marked], group[n_marked]) {
+
+                self.mark(&group[..n_marked], group[n_marked], true);
+                n_marked += 1;
+            }
+            if n_marked == group.len() {
+                self.short.push(group.to_vec());
+                if self.next_short(n_short) {
+                    return true;
+                }
+                self.short.pop();
+            }
+            for i in (0..n_marked).rev() {
+                self.mark(&group[..i], group[i], false);
+            }
+        }
+        false
+    }
+    /// Finds the groups after those already in 'groups'. The next group is
+    /// started with the participant who has the fewest others left that they
+    /// could join, and the search backs up as soon as someone is left without
+    /// enough. The others are tried with those who have the fewest first.
+    fn next_group(&mut self) -> bool {
+        let n_points   = self.placed.len();
+        let mut pivot  = None;
+        let mut fewest = usize::MAX;
+        let mut n_ties = 0;
+        let mut counts = vec![0; n_points];
+
+        for (p, count) in counts.iter_mut().enumerate() {
+            if self.placed[p] { continue; }
+            let n_partners = (0..n_points).filter(|&q| q != p 
+                                                       && !self.placed[q]
+                                                       && self.fits_pair(p, q))
+                                          .count();
+            *count = n_partners;
+            if n_partners < self.size - 1 {
+                return false;
+            }
+            if n_partners < fewest {
+                fewest = n_partners;
+                pivot  = Some(p);
+                n_ties = 1;
+            } else if n_partners == fewest {
+                n_ties += 1;
+                if self.rng.gen_range(0, n_ties) == 0 {
+                    pivot = Some(p);
+                }
+            }
+        }
+        let pivot = match pivot {
+            Some(p) => p,
+            None    => return true,
+        };
+        let mut partners = (0..n_points).filter(|&q| {
+                                            q != pivot 
+                                                && !self.placed[q]
+                                                && self.fits_pair(pivot, q)
+                                        })
+                                        .collect::<Vec<_>>();
+        partners.shuffle(&mut self.rng);
+        partners.sort_by_key(|&q| counts[q]);
+        self.placed[pivot] = true;
+        let found = self.fill(&mut vec![pivot], &partners, 0);
+        self.placed[pivot] = false;
+        found
+    }
+    /// Adds members from 'partners', from position 'from' on, to the group
+    /// until it's full, then finds the groups after it.
+    fn fill(&mut self, group: &mut Vec<usize>, partners: &[usize], from: usize)
+        -> bool
+    {
+        if group.len() == self.size {
+            self.groups.push(group.clone());
+            if self.next_group() {
+                return true;
+            }
+            self.groups.pop();
+            return false;
+        }
+        for (i, &p) in partners.iter().enumerate().skip(from) {
+            if self.nodes >= self.max_nodes {
+                return false;
+            }
+            if !self.fits(group, p) { continue; }
+            self.nodes += 1;
+            self.mark(group, p, true);
+            self.placed[p] = true;
+            group.push(p);
+
+            if self.fill(group, partners, i + 1) {
+                return true;
+            }
+            group.pop();
+            self.placed[p] = false;
+            self.mark(group, p, false);
+        }
+        false
+    }
+}
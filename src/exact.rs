@@ -0,0 +1,228 @@
+
+use crate::participant::*;
+use crate::participant_set::*;
+use crate::schedule::*;
+use crate::solver::*;
+
+/// The outcome of an exact search.
+#[derive(Clone, Debug)]
+pub struct ExactResult {
+    /// Whether a schedule was found.
+    pub solved    : bool,
+    /// Whether the search ran to completion. If it did and no schedule was
+    /// found, no schedule exists for the problem.
+    pub exhausted : bool,
+    /// The number of nodes, placements of a participant, explored.
+    pub nodes     : u64,
+    /// The schedule found, or the one with the most placements reached.
+    pub best      : Schedule,
+}
+impl ExactResult {
+    /// Indicates whether the search proved that no schedule exists.
+    pub fn infeasible(&self) -> bool {
+        self.exhausted && !self.solved
+    }
+}
+
+/// How a branch of the search ended.
+enum Branch {
+    Found,
+    Exhausted,
+    Aborted,
+}
+
+/// The exact solver.
+/// A complete depth first search that either finds a schedule or proves that
+/// none exists. Many schedules are the same apart from the labels of the
+/// participants and the order of the rounds and groups, so only one of each
+/// is considered:
+/// - The first round is fixed, with participants grouped in order.
+/// - Groups hold their members in increasing order, and the groups of a round
+///   are ordered by their smallest member. The first member of each group is
+///   then fixed as the smallest participant not yet placed in the round.
+/// - Rounds after the first are ordered by the second member of the group of
+///   participant 1.
+///
+/// The search is also cut short when a participant can no longer meet enough
+/// new people to fill the remaining rounds. The problem's number of attempts
+/// is the limit on the number of nodes explored.
+pub struct ExactSearch {
+    problem  : Problem,
+    acqs     : Vec<ParticipantSet>,
+    placed   : Vec<ParticipantSet>,
+    groups   : Vec<Vec<Vec<usize>>>,
+    nodes    : u64,
+    most     : usize,
+    best     : Schedule,
+    cancel   : CancelToken,
+}
+impl ExactSearch {
+    /// Creates an ExactSearch for the problem. The problem is expected to
+    /// have been validated.
+    pub fn new(problem: Problem) -> Self {
+        let acqs   = vec![ParticipantSet::new(); problem.num_participants];
+        let placed = vec![ParticipantSet::new(); problem.num_rounds];
+        let groups = vec![vec![vec![]; problem.num_groups];
+                          problem.num_rounds];
+        ExactSearch { problem, acqs, placed, groups, nodes: 0, most: 0,
+                      best: Schedule::default(), cancel: CancelToken::new() }
+    }
+    /// Makes the search stop, as if it had reached the node limit, once
+    /// 'cancel' is cancelled.
+    pub fn set_cancel(&mut self, cancel: CancelToken) {
+        self.cancel = cancel;
+    }
+    /// Returns the number of nodes explored so far.
+    pub fn nodes(&self) -> u64 {
+        self.nodes
+    }
+    /// Searches until a schedule is found, the search space is exhausted or
+    /// the node limit is reached.
+    pub fn solve(&mut self) -> ExactResult {
+        self.solve_with(|_| {})
+    }
+    /// Searches like `solve()`, calling 'on_best' each time the search
+    /// reaches more placements than before. Problems with a bye, which only
+    /// the round robin construction can schedule, are returned unsolved at
+    /// once.
+    pub fn solve_with<F>(&mut self, mut on_best: F) -> ExactResult
+    where
+        F: FnMut(&Schedule),
+    {
+        let n_parts = self.problem.num_participants;
+        let size    = self.problem.group_size();
+
+        if self.problem.has_bye() {
+            return ExactResult { solved: false, exhausted: false, nodes: 0,
+                                 best: self.schedule() };
+        }
+
+        // Each round a participant meets size - 1 new people, so there must be
+        // enough others to go around.
+        if (size - 1) * self.problem.num_rounds > n_parts - 1 {
+            return ExactResult { solved: false, exhausted: true, nodes: 0,
+                                 best: self.schedule() };
+        }
+        // The first round is fixed.
+        for p in 0..n_parts {
+            self.place(0, p / size, p);
+        }
+        self.most = n_parts;
+        self.best = self.schedule();
+        on_best(&self.best);
+
+        let branch = self.search(1, 0, &mut on_best);
+
+        ExactResult { solved    : matches!(branch, Branch::Found),
+                      exhausted : !matches!(branch, Branch::Aborted),
+                      nodes     : self.nodes,
+                      best      : self.best.clone() }
+    }
+    /// Returns the groupings placed so far.
+    fn schedule(&self) -> Schedule {
+        Schedule::new(self.problem.num_participants,
+                      self.problem.group_size(),
+                      self.groups.clone())
+    }
+    /// Places 'p' in group 'g' of round 'r' and acquaints it with the group.
+    fn place(&mut self, r: usize, g: usize, p: usize) {
+        let hp = HParticipant { idx: p };
+        for &m in &self.groups[r][g] {
+            self.acqs[m].add(hp);
+            self.acqs[p].add(HParticipant { idx: m });
+        }
+        self.groups[r][g].push(p);
+        self.placed[r].add(hp);
+    }
+    /// Undoes the last placement in group 'g' of round 'r'.
+    fn unplace(&mut self, r: usize, g: usize) {
+        let p  = self.groups[r][g].pop().unwrap();
+        let hp = HParticipant { idx: p };
+        for &m in &self.groups[r][g] {
+            self.acqs[m].remove(hp);
+            self.acqs[p].remove(HParticipant { idx: m });
+        }
+        self.placed[r].remove(hp);
+    }
+    /// Indicates whether every participant can still meet enough new people
+    /// to fill the rounds after round 'r'.
+    fn can_finish(&self, r: usize) -> bool {
+        let n_parts = self.problem.num_participants;
+        let needed  = (self.problem.group_size() - 1)
+                    * (self.problem.num_rounds - r - 1);
+        self.acqs.iter().all(|a| n_parts - 1 - a.count() as usize >= needed)
+    }
+    /// Fills round 'r' from group 'g' on, then the rounds after it.
+    fn search<F>(&mut self, r: usize, g: usize, on_best: &mut F) -> Branch
+    where
+        F: FnMut(&Schedule),
+    {
+        if r == self.problem.num_rounds {
+            return Branch::Found;
+        }
+        if g == self.problem.num_groups {
+            if !self.can_finish(r) {
+                return Branch::Exhausted;
+            }
+            return self.search(r + 1, 0, on_best);
+        }
+        // The group starts with the smallest participant not yet placed.
+        let first = (0..self.problem.num_participants)
+                        .find(|&p| !self.placed[r].has(HParticipant { idx: p }))
+                        .unwrap();
+        self.place(r, g, first);
+        let branch = self.fill(r, g, first, on_best);
+        self.unplace(r, g);
+        branch
+    }
+    /// Adds members greater than 'last' to group 'g' of round 'r' until it's
+    /// full, then goes on to the next group.
+    fn fill<F>(&mut self, r: usize, g: usize, last: usize, on_best: &mut F)
+        -> Branch
+    where
+        F: FnMut(&Schedule),
+    {
+        if self.groups[r][g].len() == self.problem.group_size() {
+            return self.search(r, g + 1, on_best);
+        }
+        // Order the rounds by the second member of the first group.
+        let mut start = last + 1;
+        if g == 0 && self.groups[r][g].len() == 1 && r > 1 {
+            start = start.max(self.groups[r - 1][0][1] + 1);
+        }
+        let mut members = ParticipantSet::new();
+        for &m in &self.groups[r][g] {
+            members.add(HParticipant { idx: m });
+        }
+        for q in start..self.problem.num_participants {
+            let hq = HParticipant { idx: q };
+            if self.placed[r].has(hq) || self.acqs[q].has_common(&members) {
+                continue;
+            }
+            if self.nodes >= self.problem.num_attempts as u64
+                || self.cancel.is_cancelled() {
+                return Branch::Aborted;
+            }
+            self.nodes += 1;
+            self.place(r, g, q);
+
+            let n_placed = r * self.problem.num_participants
+                         + self.placed[r].count() as usize;
+            if n_placed > self.most {
+                self.most = n_placed;
+                self.best = self.schedule();
+                on_best(&self.best);
+            }
+            let branch = self.fill(r, g, q, on_best);
+            self.unplace(r, g);
+
+            match branch {
+                Branch::Exhausted => {},
+                _                 => return branch,
+            }
+        }
+        Branch::Exhausted
+    }
+}
+
+
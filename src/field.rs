@@ -0,0 +1,107 @@
+
+/// A finite field GF(q) for a prime power q, with elements numbered 0..q.
+/// An element is a polynomial over GF(p) of degree less than m, where q = p^m,
+/// numbered by reading its coefficients as the digits of a base p number.
+/// Element 0 is zero and element 1 is one. Sums and products are looked up in
+/// tables, which is fine for the small orders schedules need.
+pub(crate) struct Field {
+    pub(crate) order : usize,
+    add              : Vec<Vec<usize>>,
+    mul              : Vec<Vec<usize>>,
+}
+impl Field {
+    /// Creates GF(q), or returns None if 'q' isn't a prime power.
+    pub(crate) fn new(q: usize) -> Option<Self> {
+        let (p, m) = prime_power(q)?;
+        let add = (0..q).map(|a| (0..q).map(|b| poly_add(a, b, p))
+                                       .collect())
+                        .collect();
+        // Try each monic polynomial of degree m until the products it gives
+        // form a field, that is until every nonzero element has an inverse.
+        for low in 0..q {
+            let modulus = q + low;
+            let mul     = (0..q).map(|a| (0..q).map(|b| poly_mul(a, b, p, m,
+                                                                 modulus))
+                                           .collect::<Vec<_>>())
+                                .collect::<Vec<_>>();
+            if (1..q).all(|a| (1..q).any(|b| mul[a][b] == 1)) {
+                return Some(Field { order: q, add, mul });
+            }
+        }
+        None
+    }
+    /// Returns a + b.
+    #[inline]
+    pub(crate) fn add(&self, a: usize, b: usize) -> usize {
+        self.add[a][b]
+    }
+    /// Returns -a.
+    pub(crate) fn neg(&self, a: usize) -> usize {
+        (0..self.order).find(|&b| self.add[a][b] == 0).unwrap()
+    }
+    /// Returns a * b.
+    #[inline]
+    pub(crate) fn mul(&self, a: usize, b: usize) -> usize {
+        self.mul[a][b]
+    }
+}
+
+/// Returns (p, m) with p prime and q = p^m, or None if 'q' isn't a prime
+/// power.
+pub(crate) fn prime_power(q: usize) -> Option<(usize, usize)> {
+    if q < 2 {
+        return None;
+    }
+    let p = (2..=q).find(|&d| q.is_multiple_of(d))?;
+    let (mut rest, mut m) = (q, 0);
+    while rest.is_multiple_of(p) {
+        rest /= p;
+        m    += 1;
+    }
+    if rest == 1 { Some((p, m)) } else { None }
+}
+
+/// Adds the polynomials 'a' and 'b' over GF(p) digit by digit.
+fn poly_add(mut a: usize, mut b: usize, p: usize) -> usize {
+    let (mut sum, mut place) = (0, 1);
+    while a > 0 || b > 0 {
+        sum   += (a % p + b % p) % p * place;
+        a     /= p;
+        b     /= p;
+        place *= p;
+    }
+    sum
+}
+
+/// Multiplies the polynomials 'a' and 'b' over GF(p) and reduces the product
+/// by the monic polynomial 'modulus' of degree 'm'.
+fn poly_mul(a: usize, b: usize, p: usize, m: usize, modulus: usize) -> usize {
+    let digits = |mut x: usize| {
+        let mut ds = vec![];
+        while x > 0 {
+            ds.push(x % p);
+            x /= p;
+        }
+        ds
+    };
+    let (da, db) = (digits(a), digits(b));
+    let dm       = digits(modulus);
+    let mut prod = vec![0; (da.len() + db.len()).max(1)];
+
+    for (i, &x) in da.iter().enumerate() {
+        for (j, &y) in db.iter().enumerate() {
+            prod[i + j] = (prod[i + j] + x * y) % p;
+        }
+    }
+    // The modulus is monic, so the leading term cancels by subtracting a
+    // multiple of it.
+    for i in (m..prod.len()).rev() {
+        let c = prod[i];
+        if c == 0 { continue; }
+        for (j, &y) in dm.iter().enumerate() {
+            let k   = i - m + j;
+            prod[k] = (prod[k] + p * p - c * y % p) % p;
+        }
+    }
+    prod.iter().take(m).rev().fold(0, |acc, &d| acc * p + d)
+}
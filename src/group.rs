@@ -8,7 +8,7 @@ pub const HGROUP_NULL: HGroup = HGroup { idx: usize::MAX };
 /// The public handles for Group objects.
 #[derive(Copy, Clone)]
 pub struct HGroup {
-    idx: usize,
+    pub (crate) idx: usize,
 }
 impl PartialEq for HGroup {
     fn eq(&self, other: &Self) -> bool {
@@ -0,0 +1,50 @@
+use crate::schedule::*;
+
+/// Where a participant is in one round.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Stop {
+    /// The id of the participant's group, numbered as in the printed
+    /// schedules, or None if they aren't in any group of the round.
+    pub group : Option<usize>,
+    /// The others in the group, in increasing order.
+    pub mates : Vec<usize>,
+}
+
+/// The groups one participant is in, round by round. This is the schedule
+/// turned around, from the groups of each round to the rounds of each
+/// participant, for handing to the participants themselves.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Itinerary {
+    /// The participant's index.
+    pub participant : usize,
+    /// The participant's stop in each round.
+    pub stops       : Vec<Stop>,
+}
+impl Itinerary {
+    /// Returns the itinerary of every participant of the schedule, by index.
+    pub fn all(schedule: &Schedule) -> Vec<Itinerary> {
+        let n_parts = schedule.num_participants();
+        let mut itineraries = (0..n_parts).map(|participant| {
+            let stops = vec![Stop { group: None, mates: vec![] };
+                             schedule.num_rounds()];
+            Itinerary { participant, stops }
+        }).collect::<Vec<_>>();
+
+        let mut group_id = 0;
+        for (round_i, round) in schedule.rounds().iter().enumerate() {
+            for group in round {
+                group_id += 1;
+                for &p in group.iter().filter(|&&p| p < n_parts) {
+                    let mut mates = group.iter()
+                                         .copied()
+                                         .filter(|&m| m != p)
+                                         .collect::<Vec<_>>();
+                    mates.sort_unstable();
+                    let stop = Stop { group: Some(group_id), mates };
+                    itineraries[p].stops[round_i] = stop;
+                }
+            }
+        }
+        itineraries
+    }
+}
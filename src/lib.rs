@@ -7,8 +7,8 @@
 //! Kirkman's Schoolgirls Problem was originally stated:
 //!
 //! >  Fifteen young ladies in a school walk out three abreast for seven days 
-//!    in succession: it is required to arrange them daily so that no two shall
-//!    walk twice abreast.
+//! >  in succession: it is required to arrange them daily so that no two shall
+//! >  walk twice abreast.
 //!
 //! There are many variations on the number of participants, the sizes and 
 //! number of the groups, and the number of rounds performed. One variation is
@@ -20,5 +20,25 @@ pub mod participant;
 pub mod participant_set;
 pub mod group;
 pub mod round;
+pub mod schedule;
+pub mod solver;
+pub mod anneal;
+pub mod tabu;
+pub mod exact;
+pub mod sat;
+pub mod canon;
+pub mod verify;
+pub mod construct;
+pub mod mols;
+pub mod rng;
+pub mod bench;
+pub mod preset;
+pub mod checkpoint;
+pub mod report;
+pub mod itinerary;
+pub mod meetings;
+
+mod assignment;
+mod field;
 
 
@@ -7,8 +7,8 @@
 //! Kirkman's Schoolgirls Problem was originally stated:
 //!
 //! >  Fifteen young ladies in a school walk out three abreast for seven days 
-//!    in succession: it is required to arrange them daily so that no two shall
-//!    walk twice abreast.
+//! >  in succession: it is required to arrange them daily so that no two shall
+//! >  walk twice abreast.
 //!
 //! There are many variations on the number of participants, the sizes and 
 //! number of the groups, and the number of rounds performed. One variation is
@@ -16,26 +16,34 @@
 //! a tournament with the same restrictions to ensure all the golfers meet
 //! and play against as many other golfers as possible.
 
-mod participant;
-mod participant_set;
-mod group;
-mod round;
+use socialx::bench::{self, *};
+use socialx::checkpoint::*;
+use socialx::construct::*;
+use socialx::exact::*;
+use socialx::preset::{self, *};
+use socialx::report::*;
+use socialx::sat::*;
+use socialx::schedule::*;
+use socialx::solver::*;
+use socialx::verify::*;
+use socialx::rng;
 
-use group::*;
-use participant::*;
-use round::*;
-
-
-use itertools::enumerate;
-use rand::prelude::*;
 use std::env;
+use std::fmt;
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Read};
 use std::process;
+use std::str::FromStr;
 use std::sync::{Arc, RwLock};
-
-// Works like Python's random.randint().
-macro_rules! randint {
-    ( $start:expr, $end:expr ) => {
-        rand::thread_rng().gen_range($start, $end + 1)
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Prints a progress or status message of a run, to standard error when the
+// output is for other programs to read.
+macro_rules! say {
+    ( $format:expr, $($arg:tt)* ) => {
+        if $format == Format::Text { println!($($arg)*); }
+        else                       { eprintln!($($arg)*); }
     };
 }
 
@@ -47,231 +55,931 @@ macro_rules! shared {
 }
 
 fn main() {
-    let num_attempts;
-                                   // Kirkman's Schoolgirl's   Conference
-    let num_participants;          //         15;                   70;
-    let num_rounds;                //          7;                    5;
-    let num_groups_per_round;      //          5;                   10;
-    
-    match parse_options() {
-        Ok(opts) => { 
-            num_attempts         = opts.n_attempts;
-            num_participants     = opts.n_participants;
-            num_rounds           = opts.n_rounds;
-            num_groups_per_round = opts.n_groups;
-        },
+    let opts = match parse_options() {
+        Ok(opts) => opts,
         Err(msg) => {
             println!("{}", &msg);
             return;
         }
+    };
+    if let Command::Verify(path) = &opts.command {
+        process::exit(verify_schedule(path, &opts));
     }
-    
-    let num_groups_total     = num_groups_per_round * num_rounds;
-    let num_regroups         = num_participants * 2;
-    let group_size           = (num_participants / num_groups_per_round) as u32;
-
-    // The aggregate objects that handles belong to in the program.
-    let mut parts  = Participants::new();
-    let mut groups = Groups::new();
-    let mut rounds = Rounds::new();
-    
-    // Allocate the objects of the program and get their handles.
-    let mut hpart_vec_a  = parts.hcalloc(num_participants);
-    let mut hpart_vec_b  = hpart_vec_a.clone();    
-    let     hgroup_vec   = groups.hcalloc(num_groups_total, group_size);
-    let     hround_vec   = rounds.hcalloc(num_rounds);
-
-    // For tracking the best distribution of the cycles.
-    let mut best_num_grouped = 0;
-    let     best_rounds_str  = shared!(String);
-
-    let brc = best_rounds_str.clone();    
-    
-    // Handler for Ctrl-C events.
+    if let Command::Presets = &opts.command {
+        print_presets();
+        return;
+    }
+    if let Command::Bench(names) = &opts.command {
+        process::exit(run_bench(names, &opts));
+    }
+    if let Some(seed) = opts.seed {
+        rng::seed(seed);
+    }
+    let resumed = match &opts.resume {
+        Some(path) => match read_checkpoint(path, &opts) {
+            Ok(cp)   => Some(cp),
+            Err(msg) => {
+                println!("Can't resume from {}: {}", path, msg);
+                return;
+            },
+        },
+        None => None,
+    };
+    let problem = match &resumed {
+        Some(cp) => cp.problem,
+        None     => Problem { num_attempts     : opts.n_attempts,
+                              num_participants : opts.n_participants,
+                              num_groups       : opts.n_groups,
+                              num_rounds       : opts.n_rounds },
+    };
+    if let Err(msg) = problem.validate() {
+        println!("{}", &msg);
+        return;
+    }
+    if let Command::SatEncode | Command::SatDecode(_) = &opts.command {
+        process::exit(run_sat(problem, &opts));
+    }
+    // A resumed search keeps writing to the checkpoint it resumed from.
+    let checkpoint = opts.checkpoint.as_ref().or(opts.resume.as_ref());
+    if checkpoint.is_some()
+        && (opts.algorithm != Algorithm::Regroup || opts.n_solutions > 1) {
+        println!("Checkpoints need the regroup algorithm and one solution.");
+        return;
+    }
+    if (opts.format != Format::Text || opts.view != View::Schedule)
+        && opts.n_solutions > 1 {
+        println!("Several solutions can only be written as text schedules.");
+        return;
+    }
+    let names = match &opts.names {
+        Some(path) => match read_names(path, &problem) {
+            Ok(names) => names,
+            Err(msg)  => {
+                println!("Can't read names from {}: {}", path, msg);
+                return;
+            },
+        },
+        None => vec![],
+    };
+    if resumed.is_some() && opts.start.is_some() {
+        println!("A resumed search keeps the starting rounds of its \
+                  checkpoint.");
+        return;
+    }
+    if opts.construct && opts.n_solutions == 1 && opts.start.is_none()
+        && resumed.is_none() {
+        if let Some(construction) = Construction::new(problem) {
+            say!(opts.format, "\nCONSTRUCTED! ({})\n", construction.design());
+            let schedule   = construction.schedule();
+            let mut report = Report::new(problem, &schedule,
+                                         Status::Constructed, "construction");
+            report.design = Some(construction.design().to_string());
+            report.names  = &names;
+            print_report(&report, &opts);
+            return;
+        }
+    }
+    if problem.has_bye() {
+        println!("An odd number of participants in pairs can only be scheduled \
+                  by the round robin construction, for up to {} rounds.",
+                 problem.num_participants);
+        return;
+    }
+    let start = match &opts.start {
+        Some(path) => match read_start(path, &problem) {
+            Ok(start) => Some(start),
+            Err(msg)  => {
+                println!("Can't start from {}: {}", path, msg);
+                return;
+            },
+        },
+        None => None,
+    };
+    if start.is_some() && opts.algorithm != Algorithm::Regroup {
+        println!("Starting from given rounds needs the regroup algorithm.");
+        return;
+    }
+    let num_placements = problem.num_placements();
+    let format         = opts.format;
+
+    // For tracking the best distribution of the cycles. The report is only
+    // formatted when it's printed.
+    let best_rounds = shared!(Schedule);
+
+    if let Some(cp) = &resumed {
+        say!(format, "Resuming after {} attempts.", cp.attempts);
+        shared!(write, best_rounds) = cp.best.clone();
+    }
+
+    // Handler for Ctrl-C events. The search stops and its results are
+    // reported as usual. A second Ctrl-C exits at once.
+    let cancel = CancelToken::new();
+    let token  = cancel.clone();
     ctrlc::set_handler(move || {
-        println!("received Ctrl+C!");
-        println!("{}", shared!(read, brc));
-        process::exit(0);
+        if token.is_cancelled() {
+            process::exit(130);
+        }
+        say!(format, "received Ctrl+C!");
+        token.cancel();
     })
     .expect("Error setting Ctrl-C handler");
-    
-    let hpart_vec_b_len = hpart_vec_b.len();
-    let mut hpart_b_idx = 0;
-    
-    #[allow(unused_labels)]
-    'start_fresh: for attempt_i in 0..num_attempts {
-        
-        // Determine if we have the best distribution so far.
-        
-        let num_grouped = rounds.num_grouped(&hround_vec, &groups);
-        
-        if num_grouped > best_num_grouped {
-            println!("Best so far: {:>3} placements out of {:>3}.", 
-                     num_grouped, num_rounds * num_participants);
-            
-            // Best so far, retain the number and string report.
-            best_num_grouped = num_grouped;
-            shared!(write, best_rounds_str) 
-                                = rounds.to_string_multi(&hround_vec, 
-                                                         &groups, 
-                                                         &parts);
+
+    if opts.n_solutions > 1 {
+        if opts.algorithm != Algorithm::Regroup {
+            println!("Finding several solutions needs the regroup algorithm.");
+            return;
         }
-        if num_grouped >= (num_rounds * num_participants) as u32 {
-            // If all groups are full, the problem is solved.
-            println!("\nSOLVED! ({} iterations)\n", attempt_i);
-            break;
+        // Keep searching after each success for more distinct solutions.
+        let mut n_found   = 0;
+        let     started   = Instant::now();
+        let mut solver    = new_solver(problem, &start, &opts);
+        solver.set_cancel(cancel.clone());
+        let mut solutions = solver.solutions();
+        for schedule in solutions.by_ref().take(opts.n_solutions) {
+            n_found += 1;
+            println!("\nSolution {}:\n", n_found);
+            println!("{}", schedule);
         }
-        if attempt_i > 0 {
-            // Reset all objects for another attempt at solving the problem.
-            parts .reset();
-            groups.reset();
-            rounds.reset();
-            parts.sort_slice(&mut hpart_vec_a);
+        if n_found < opts.n_solutions {
+            println!("\nFound {} of {} distinct solutions before the \
+                      {}.", n_found, opts.n_solutions,
+                     if cancel.is_cancelled() { "search was stopped" }
+                     else                     { "attempts ran out" });
         }
-        
-        #[allow(unused_labels)]
-        'another_round: for (round_i, &hround) in enumerate(&hround_vec) {
-            // Slice the groups per round.
-            let n_groups     = num_groups_per_round;
-            let gr_start     = n_groups * round_i;
-            let gr_end       = gr_start + n_groups;
-            
-            let hgroup_slice = &hgroup_vec[gr_start..gr_end];
-            
-            // Add the groups slice to the current round.
-            rounds.add_groups(hround, hgroup_slice);
-            
-            // Prepare the participants to be grouped again.
-            parts.prepare_for_new_round();
-            
-            if round_i > 0 {
-                // Randomize the order in which participants are grouped after
-                // the first round.
-                shuffle!(hpart_vec_a);
-                shuffle!(hpart_vec_b);
-                hpart_b_idx = 0;
+        if opts.stats {
+            print_stats(&solutions.solver().stats(), started);
+        }
+        return;
+    }
+
+    // Pressing Enter at a terminal prints the best so far and lets the
+    // search go on. Several solutions are printed as they're found instead.
+    if io::stdin().is_terminal() {
+        let brc = best_rounds.clone();
+        thread::spawn(move || {
+            for _ in io::stdin().lock().lines() {
+                say!(format, "{}", shared!(read, brc));
             }
-     
-            'grouping_participants: for &hpart_a in &hpart_vec_a {
-                
-                'trying_regroups: for _ in 0..num_regroups {
-                    
-                    // Try to find a group for hpart_a.
-                    if parts.try_join_groups(hpart_a, 
-                                             hround,
-                                             &rounds, 
-                                             &mut groups ) {
-                                             
-                        // Participant found group, move to next participant.
-                        continue 'grouping_participants;
-                    } else {
-                        // Didn't find a group - get another participant to 
-                        // regroup to see if an opening can be made.
-
-                        for _ in 0..hpart_vec_b_len {
-                            hpart_b_idx += 1;
-                            hpart_b_idx %= hpart_vec_b_len;
-                            let hpart_b  = hpart_vec_b[hpart_b_idx];
-                        
-                            if !parts.is_grouped(hpart_b) { continue; }   
-                            
-                            // Pick a round to make the move in.
-                            let round_num = randint!(1, round_i);
-                            
-                            // Attempt the regroup. On success go back and try
-                            // again to group hpart_a.
-                            if parts.try_regroup(hpart_b, 
-                                                 hround_vec[round_num],
-                                                 &rounds,
-                                                 &mut groups ).is_ok() {
-                                                      
-                                    continue 'trying_regroups;
-                            }
-                        }
-                        // The regroup loop completed, which means all the other
-                        // participants tried to regroup and none succeeded.
-                        continue 'start_fresh;
+        });
+    }
+
+    let mut on_best = |best: &Schedule| {
+        say!(format, "Best so far: {:>3} placements out of {:>3}.", 
+             best.num_placements(), num_placements);
+
+        // Best so far, retain it for the report.
+        shared!(write, best_rounds) = best.clone();
+    };
+    let started = Instant::now();
+    let (status, result) = if opts.algorithm == Algorithm::Exact {
+        let mut exact = ExactSearch::new(problem);
+        exact.set_cancel(cancel.clone());
+        let result = exact.solve_with(&mut on_best);
+        let status = if result.solved {
+            say!(format, "\nSOLVED! ({} nodes explored)\n", result.nodes);
+            Status::Solved
+        } else if result.infeasible() {
+            say!(format, "\nNo schedule exists for {} participants in {} \
+                          groups over {} rounds ({} nodes explored).\n", 
+                 problem.num_participants, problem.num_groups, 
+                 problem.num_rounds, result.nodes);
+            Status::Infeasible
+        } else if cancel.is_cancelled() {
+            say!(format, "\nStopped after {} nodes.\n", result.nodes);
+            Status::Stopped
+        } else {
+            say!(format, "\nGave up after {} nodes without finding a \
+                          schedule or showing that none exists.\n",
+                 result.nodes);
+            Status::Unsolved
+        };
+        (status, SolveResult { solved   : result.solved,
+                               attempts : result.nodes as usize,
+                               best     : result.best,
+                               stats    : None })
+    } else {
+        let result = if opts.algorithm == Algorithm::Regroup {
+            let mut solver = match &resumed {
+                Some(cp) => Solver::from_checkpoint(cp),
+                None     => new_solver(problem, &start, &opts),
+            };
+            solver.set_cancel(cancel.clone());
+            if let Some(path) = checkpoint {
+                let path  = path.clone();
+                let every = Duration::from_secs(opts.checkpoint_every as u64);
+                solver.set_checkpoints(every, move |cp| {
+                    if let Err(msg) = write_checkpoint(&path, cp) {
+                        say!(format, "Can't write checkpoint to {}: {}",
+                             path, msg);
                     }
-                }
+                });
+            }
+            solver.solve_with(&mut on_best)
+        } else {
+            opts.algorithm.solve_until(problem, &cancel, &mut on_best)
+        };
+        let status = if result.solved {
+            say!(format, "\nSOLVED! ({} iterations)\n", result.attempts);
+            Status::Solved
+        } else if cancel.is_cancelled() {
+            say!(format, "\nStopped after {} iterations.\n", result.attempts);
+            Status::Stopped
+        } else {
+            Status::Unsolved
+        };
+        if opts.stats && opts.format == Format::Text {
+            match &result.stats {
+                Some(stats) => print_stats(stats, started),
+                None        => println!("Only the regroup algorithm collects \
+                                         statistics.\n"),
             }
         }
-    }
+        (status, result)
+    };
     // The results are...
-    println!("{}", shared!(read, best_rounds_str));
+    let method     = opts.algorithm.to_string();
+    let mut report = Report::new(problem, &result.best, status, &method);
+    report.names    = &names;
+    report.attempts = Some(result.attempts);
+    report.seconds  = Some(started.elapsed().as_secs_f64());
+    report.stats    = result.stats;
+    print_report(&report, &opts);
+}
+
+enum Command {
+    Solve,
+    Verify(String),
+    SatEncode,
+    SatDecode(String),
+    Bench(Vec<String>),
+    Presets,
 }
 
 struct Options {
+    command         : Command,
     n_attempts      : usize,
     n_participants  : usize,
     n_groups        : usize,
     n_rounds        : usize,
+    n_solutions     : usize,
+    algorithm       : Algorithm,
+    set_participants: bool,
+    set_groups      : bool,
+    set_attempts    : bool,
+    forbidden       : Vec<(usize, usize)>,
+    pins            : Vec<(usize, usize, usize)>,
+    symmetry        : bool,
+    construct       : bool,
+    start           : Option<String>,
+    placement       : Placement,
+    restart         : Restart,
+    regroup         : RegroupChoice,
+    stats           : bool,
+    seed            : Option<u64>,
+    runs            : usize,
+    csv             : bool,
+    checkpoint      : Option<String>,
+    checkpoint_every: usize,
+    resume          : Option<String>,
+    format          : Format,
+    names           : Option<String>,
+    view            : View,
+}
+
+/// What's shown of the schedule of a run.
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum View {
+    /// The groups of each round.
+    Schedule,
+    /// The group and group-mates of each participant in each round.
+    Itinerary,
+    /// The round each pair met in, and the pairs that never met.
+    Meetings,
+}
+impl FromStr for View {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "schedule"  => Ok(View::Schedule),
+            "itinerary" => Ok(View::Itinerary),
+            "meetings"  => Ok(View::Meetings),
+            _           => Err(format!("Unknown view {}.", s)),
+        }
+    }
+}
+
+/// How the schedule of a run is written.
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Format {
+    Text,
+    Json,
+    Csv,
+    CsvWide,
 }
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text"     => Ok(Format::Text),
+            "json"     => Ok(Format::Json),
+            "csv"      => Ok(Format::Csv),
+            "csv-wide" => Ok(Format::CsvWide),
+            _          => Err(format!("Unknown format {}.", s)),
+        }
+    }
+}
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Format::Text    => write!(f, "text"),
+            Format::Json    => write!(f, "json"),
+            Format::Csv     => write!(f, "csv"),
+            Format::CsvWide => write!(f, "csv-wide"),
+        }
+    }
+}
+
+const USAGE: &str = "\
+usage: socialx [-h] | [--preset NAME] [-a A] [-p P] [-g G] [-r R] [-s S]
+                      [--algorithm NAME] [--no-construct] [--start FILE]
+                      [--placement NAME] [--restart NAME] [--regroup NAME]
+                      [--stats] [--checkpoint FILE] [--checkpoint-every SECS]
+                      [--resume FILE] [--format NAME] [--names FILE]
+                      [--view NAME]
+       socialx verify FILE [-p P] [-g G]
+       socialx sat-encode [-p P] [-g G] [-r R] [--symmetry]
+                          [--forbid A,B]... [--pin P,R,G]...
+       socialx sat-decode FILE [-p P] [-g G] [-r R]
+       socialx bench [NAME]... [--runs N] [--seed S] [--csv] [-a A]
+                     [--algorithm NAME] [--placement NAME] ...
+       socialx presets
+
+An approach to solving problems modeled after \"Kirkman's Schoolgirl Problem\".
+
+Commands:
+  verify FILE
+             check a printed schedule for repeated pairs,
+             missing or doubly placed participants and
+             group sizes. FILE may be - for standard input.
+  sat-encode write the problem as DIMACS CNF for a SAT solver.
+  sat-decode FILE
+             read the model a SAT solver found for the problem,
+             print it as a schedule and verify it.
+  bench [NAME]...
+             run each instance of the benchmark catalog, or
+             those named, several times with different seeds,
+             and report the success rate and the median and
+             95th percentile times to solve. Constructions are
+             not used. A replaces the attempts of each instance.
+  presets    list the presets.
+
+Optional Arguments:
+  -h, --help show this message and exit.
+  --preset NAME
+             set P, G and R to those of a well-known problem,
+             with its best known number of rounds. Options
+             after it override them. There's no sylvester
+             preset, since its schedules repeat pairs.
+  -a A       number of attempts to solve (1_000_000).
+  -p P       number of participants (70).
+  -g G       number of groups per round (10).
+  -r R       number of rounds (5).
+  -s, --solutions S
+             number of distinct solutions to find (1).
+  --algorithm NAME
+             search algorithm: regroup, anneal, tabu or exact
+             (regroup). For exact, A limits the nodes explored.
+  --no-construct
+             search even when the schedule can be built from a
+             known design.
+  --start FILE
+             keep the rounds of a saved schedule, such as a
+             construction with fewer rounds than wanted, and
+             search for the rest. Needs the regroup algorithm.
+  --placement NAME
+             order in which the regroup algorithm places the
+             participants of a round: random, or constrained
+             for the one with the fewest open groups first
+             (random).
+  --restart NAME
+             what the regroup algorithm does when stuck: full,
+             to start over, or partial, to redo only the last
+             rounds, as many as the Luby sequence says (full).
+  --regroup NAME
+             how the regroup algorithm picks regroups for a
+             participant who can't be grouped: random, or
+             conflict to first undo the meetings keeping them
+             out of the open groups (random).
+  --stats    print counts of the regroup algorithm's work, such
+             as its restarts, the rounds they happened in and
+             the outcomes of its regroups, and the time taken.
+  --seed S   seed the random choices so a run can be repeated.
+             For bench, the first run's seed (1).
+  --checkpoint FILE
+             write the state of the regroup algorithm's search
+             to FILE every SECS, so it can be resumed. Only
+             the regroup algorithm finding one solution has
+             checkpoints; anneal, tabu and exact runs can't
+             be resumed.
+  --checkpoint-every SECS
+             seconds between checkpoints (60).
+  --resume FILE
+             carry on the search of a checkpoint, writing new
+             checkpoints to the same FILE. The problem and
+             settings are the checkpoint's. A, if given,
+             replaces its limit on the total attempts.
+  --format NAME
+             how the schedule is written: text, or for other
+             programs to read, json, csv with a row for each
+             placement, or csv-wide with a row for each
+             participant and a column for each round. Progress
+             messages then go to standard error (text).
+  --names FILE
+             names of the participants, one per line in the
+             order of their ids, for json and csv and in place
+             of ids in the text itinerary.
+  --view NAME
+             what is written: schedule, the groups of each
+             round, itinerary, the group and group-mates of
+             each participant in each round, or meetings, a
+             table of the round each pair met in and a list of
+             the pairs that never met, for text, json or csv
+             (schedule).
+  --runs N   number of runs of each bench instance (10).
+  --csv      write the bench results as CSV.
+  --symmetry add symmetry breaking clauses to the CNF.
+  --forbid A,B
+             keep participants A and B apart in the CNF.
+  --pin P,R,G
+             place participant P in group G of round R in the
+             CNF. Groups are numbered from 1 in each round.
+";
 
 fn parse_options() -> Result<Options, String>
 {
-    let     args = env::args().collect::<Vec<_>>();
-    let mut opts = Options { n_attempts: 1_000_000, n_participants: 70,
-                             n_groups  :        10, n_rounds      :  5 };
-    for pair in args[1..].chunks(2) {
-        let mut pair = pair.into_iter();
-        let     opt  = pair.next().unwrap().as_str();
-        let mut getv = || pair.next()
+    let mut args = env::args().skip(1).peekable();
+    let mut opts = Options { command    : Command::Solve,
+                             n_attempts : 1_000_000, n_participants : 70,
+                             n_groups   :        10, n_rounds       :  5,
+                             n_solutions:         1, 
+                             algorithm  : Algorithm::Regroup,
+                             set_participants: false, set_groups: false,
+                             forbidden  : vec![], pins: vec![],
+                             symmetry   : false, construct: true,
+                             start      : None,
+                             placement  : Placement::Random,
+                             restart    : Restart::Full,
+                             regroup    : RegroupChoice::Random,
+                             stats      : false, seed: None,
+                             runs       : 10, csv: false,
+                             set_attempts: false,
+                             checkpoint : None, checkpoint_every: 60,
+                             resume     : None, format: Format::Text,
+                             names      : None, view: View::Schedule };
+
+    match args.peek().map(String::as_str) {
+        Some("verify") => {
+            args.next();
+            let path = args.next().ok_or("Missing file for verify.")?;
+            opts.command = Command::Verify(path);
+        },
+        Some("sat-encode") => {
+            args.next();
+            opts.command = Command::SatEncode;
+        },
+        Some("sat-decode") => {
+            args.next();
+            let path = args.next().ok_or("Missing file for sat-decode.")?;
+            opts.command = Command::SatDecode(path);
+        },
+        Some("presets") => {
+            args.next();
+            opts.command = Command::Presets;
+        },
+        Some("bench") => {
+            args.next();
+            let mut names = vec![];
+            while let Some(name) = args.next_if(|arg| !arg.starts_with('-')) {
+                if !CATALOG.iter().any(|inst| inst.name == name) {
+                    Err(format!("Unknown bench instance {}.", name))?;
+                }
+                names.push(name);
+            }
+            opts.command = Command::Bench(names);
+        },
+        _ => {},
+    }
+    while let Some(opt) = args.next() {
+        let mut getv = || args.next()
                               .ok_or(format!("Missing value for {}.", opt))?
                               .parse::<usize>()
                               .map_err(|s| format!("Invalid value \
                                                    ({}) for {}.", s, opt));
-        match opt {
+        match opt.as_str() {
             "-a" => { 
                 opts.n_attempts = getv()?; 
+                opts.set_attempts = true;
             },
             "-p" => { 
                 opts.n_participants = getv()?; 
+                opts.set_participants = true;
             },
             "-g" => { 
                 opts.n_groups = getv()?; 
+                opts.set_groups = true;
             },
             "-r" => { 
                 opts.n_rounds = getv()?; 
             },
+            "--preset" => {
+                let name   = args.next()
+                                 .ok_or(format!("Missing value for {}.", opt))?;
+                let preset = match (preset::find(&name),
+                                    preset::unmodeled(&name)) {
+                    (Some(preset), _) => preset,
+                    (None, Some(why)) => {
+                        return Err(format!("No preset {}. {}", name, why));
+                    },
+                    (None, None) => {
+                        return Err(format!("Unknown preset {}. See socialx \
+                                            presets.", name));
+                    },
+                };
+                opts.n_participants   = preset.num_participants;
+                opts.n_groups         = preset.num_groups;
+                opts.n_rounds         = preset.best_known;
+                opts.set_participants = true;
+                opts.set_groups       = true;
+            },
+            "-s" | "--solutions" => { 
+                opts.n_solutions = getv()?; 
+            },
+            "--algorithm" => {
+                opts.algorithm = args.next()
+                                     .ok_or(format!("Missing value for {}.", 
+                                                    opt))?
+                                     .parse()?;
+            },
+            "--forbid" => {
+                let ids = get_ids(args.next(), &opt, 2)?;
+                opts.forbidden.push((ids[0], ids[1]));
+            },
+            "--pin" => {
+                let ids = get_ids(args.next(), &opt, 3)?;
+                opts.pins.push((ids[0], ids[1], ids[2]));
+            },
+            "--symmetry" => {
+                opts.symmetry = true;
+            },
+            "--no-construct" => {
+                opts.construct = false;
+            },
+            "--start" => {
+                opts.start = Some(args.next()
+                                      .ok_or(format!("Missing value for {}.",
+                                                     opt))?);
+            },
+            "--placement" => {
+                opts.placement = args.next()
+                                     .ok_or(format!("Missing value for {}.",
+                                                    opt))?
+                                     .parse()?;
+            },
+            "--restart" => {
+                opts.restart = args.next()
+                                   .ok_or(format!("Missing value for {}.",
+                                                  opt))?
+                                   .parse()?;
+            },
+            "--regroup" => {
+                opts.regroup = args.next()
+                                   .ok_or(format!("Missing value for {}.",
+                                                  opt))?
+                                   .parse()?;
+            },
+            "--stats" => {
+                opts.stats = true;
+            },
+            "--seed" => {
+                opts.seed = Some(getv()? as u64);
+            },
+            "--runs" => {
+                opts.runs = getv()?;
+            },
+            "--csv" => {
+                opts.csv = true;
+            },
+            "--checkpoint" => {
+                opts.checkpoint = Some(args.next()
+                                           .ok_or(format!("Missing value \
+                                                           for {}.", opt))?);
+            },
+            "--checkpoint-every" => {
+                opts.checkpoint_every = getv()?;
+            },
+            "--format" => {
+                opts.format = args.next()
+                                  .ok_or(format!("Missing value for {}.",
+                                                 opt))?
+                                  .parse()?;
+            },
+            "--view" => {
+                opts.view = args.next()
+                                .ok_or(format!("Missing value for {}.", opt))?
+                                .parse()?;
+            },
+            "--names" => {
+                opts.names = Some(args.next()
+                                      .ok_or(format!("Missing value for {}.",
+                                                     opt))?);
+            },
+            "--resume" => {
+                opts.resume = Some(args.next()
+                                       .ok_or(format!("Missing value for {}.",
+                                                      opt))?);
+            },
             "-h" | "--h" | "--help" => {
-                Err("usage: socialx [-h] | [-a A] [-p P] [-g G] [-r R]\n\n\
-                     An approach to solving problems modeled after \
-                     \"Kirkman's Schoolgirl Problem\".\n\n\
-                     Optional Arguments:\n  \
-                       -h, --help show this message and exit.\n  \
-                       -a A       number of attempts to solve (1_000_000).\n  \
-                       -p P       number of participants (70).\n  \
-                       -g G       number of groups per round (10).\n  \
-                       -r R       number of rounds (5).\n")?;
+                Err(USAGE)?;
             },
             _    => {
                 Err(format!("Unknown option {}.", opt))?;
             },
         }
     }
+    if opts.view != View::Schedule && opts.format == Format::CsvWide {
+        Err("Only the schedule can be written as csv-wide.")?;
+    }
     Ok(opts)
 }
 
+/// Parses a value of 'n' comma separated ids, numbered from 1, into indices.
+fn get_ids(value: Option<String>, opt: &str, n: usize) 
+    -> Result<Vec<usize>, String>
+{
+    let value = value.ok_or(format!("Missing value for {}.", opt))?;
+    let ids   = value.split(',')
+                     .map(|id| id.trim().parse::<usize>().ok()
+                                 .filter(|&id| id > 0)
+                                 .map(|id| id - 1))
+                     .collect::<Option<Vec<_>>>()
+                     .filter(|ids| ids.len() == n)
+                     .ok_or(format!("Invalid value ({}) for {}.", value, opt))?;
+    Ok(ids)
+}
 
+/// Reads the text of 'path', or of standard input if 'path' is -.
+fn read_input(path: &str) -> Result<String, String> {
+    let text = if path == "-" {
+        let mut text = String::new();
+        io::stdin().read_to_string(&mut text).map(|_| text)
+    } else {
+        fs::read_to_string(path)
+    };
+    text.map_err(|e| e.to_string())
+}
 
+/// Prints the violations of the schedule. Returns the exit code: 0 if the
+/// schedule passed and 1 if it failed.
+fn report_violations(schedule: &Schedule) -> i32 {
+    let violations = verify(schedule);
+    for violation in &violations {
+        println!("{}", violation);
+    }
+    if violations.is_empty() {
+        println!("PASSED: {} participants, {} rounds, no problems found.",
+                 schedule.num_participants(), schedule.num_rounds());
+        0
+    } else {
+        println!("\nFAILED: {} problems found.", violations.len());
+        1
+    }
+}
 
+/// Reads the schedule in 'path' and prints its violations. Returns the exit
+/// code: 0 if the schedule passed, 1 if it failed, and 2 if it couldn't be
+/// read.
+fn verify_schedule(path: &str, opts: &Options) -> i32 {
+    let schedule = match read_input(path)
+                             .and_then(|text| text.parse::<Schedule>()) {
+        Ok(schedule) => schedule,
+        Err(msg)     => {
+            println!("Can't read schedule from {}: {}", path, msg);
+            return 2;
+        }
+    };
+    // Parameters given on the command line take the place of those inferred
+    // from the file.
+    let n_parts  = if opts.set_participants { opts.n_participants }
+                   else                     { schedule.num_participants() };
+    let n_groups = if opts.set_groups       { opts.n_groups }
+                   else                     { schedule.rounds()[0].len() };
+    let schedule = schedule.with_parameters(n_parts, 
+                                            Schedule::group_size_for(n_parts,
+                                                                     n_groups));
+    report_violations(&schedule)
+}
 
+/// Creates the regroup solver for the problem with the placement order,
+/// restart strategy and regroup choice of the options, keeping the starting
+/// rounds if there are any.
+fn new_solver(problem: Problem, start: &Option<Schedule>, opts: &Options)
+    -> Solver
+{
+    let mut solver = match start {
+        Some(start) => Solver::with_start(problem, start),
+        None        => Solver::new(problem),
+    };
+    solver.set_placement(opts.placement);
+    solver.set_restart(opts.restart);
+    solver.set_regroup_choice(opts.regroup);
+    solver
+}
 
+/// Prints the report in the view and format of the options.
+fn print_report(report: &Report, opts: &Options) {
+    let text = match (opts.view, opts.format) {
+        (View::Schedule,  Format::Text)    => format!("{}\n", report.schedule),
+        (View::Schedule,  Format::Json)    => report.to_json(),
+        (View::Schedule,  Format::Csv)     => report.to_csv(),
+        (View::Schedule,  Format::CsvWide) => report.to_csv_wide(),
+        (View::Itinerary, Format::Text)    => report.itinerary_text(),
+        (View::Itinerary, Format::Json)    => report.itinerary_json(),
+        (View::Itinerary, Format::Csv)     => report.itinerary_csv(),
+        (View::Meetings,  Format::Text)    => report.meetings_text(),
+        (View::Meetings,  Format::Json)    => report.meetings_json(),
+        (View::Meetings,  Format::Csv)     => report.meetings_csv(),
+        (_,               Format::CsvWide) => {
+            unreachable!("ruled out by parse_options()")
+        },
+    };
+    print!("{}", text);
+}
 
+/// Reads the names of the participants in 'path', one per line in the order
+/// of their ids.
+fn read_names(path: &str, problem: &Problem) -> Result<Vec<String>, String> {
+    let names = read_input(path)?.lines()
+                                 .map(|line| line.trim().to_string())
+                                 .collect::<Vec<_>>();
+    if names.len() > problem.num_participants {
+        return Err(format!("it has {} names, more than the {} participants.",
+                           names.len(), problem.num_participants));
+    }
+    Ok(names)
+}
 
+/// Reads the checkpoint in 'path'. The attempts given on the command line,
+/// if any, take the place of the checkpoint's limit.
+fn read_checkpoint(path: &str, opts: &Options) -> Result<Checkpoint, String> {
+    let mut cp = read_input(path)?.parse::<Checkpoint>()?;
+    if opts.set_attempts {
+        cp.problem.num_attempts = opts.n_attempts;
+    }
+    Ok(cp)
+}
 
+/// Writes the checkpoint to 'path'. It's written to a file beside it first
+/// and renamed over it, so a run stopped while writing leaves the last
+/// checkpoint whole.
+fn write_checkpoint(path: &str, cp: &Checkpoint) -> io::Result<()> {
+    let tmp = format!("{}.tmp", path);
+    fs::write(&tmp, cp.to_string())?;
+    fs::rename(&tmp, path)
+}
 
+/// Prints the solver's counts and the time taken since 'started'.
+fn print_stats(stats: &SolverStats, started: Instant) {
+    let secs = started.elapsed().as_secs_f64();
+    println!("Attempts          : {:>12}", stats.attempts);
+    println!("Restarts          : {:>12}", stats.restarts);
+    println!("  Partial         : {:>12}", stats.partial);
+    println!("Deepest round     : {:>12}", stats.deepest);
+    println!("Blocked placements: {:>12}", stats.blocked);
+    println!("Regroups tried    : {:>12}", stats.regroups);
+    println!("  Moved to a seat : {:>12}", stats.moved);
+    println!("  Swapped         : {:>12}", stats.swapped);
+    println!("  Failed          : {:>12}", stats.failed);
+    println!("Restarts by round :");
+    for (round_i, &n) in stats.restarts_by_round.iter().enumerate() {
+        if n > 0 {
+            println!("  Round {:<10}: {:>12}", round_i + 1, n);
+        }
+    }
+    println!("Seconds           : {:>12.3}", secs);
+    if secs > 0.0 {
+        println!("Attempts/second   : {:>12.0}", stats.attempts as f64 / secs);
+    }
+}
 
+/// Prints the presets with their parameters and descriptions.
+fn print_presets() {
+    println!("{:<11} {:>5} {:>6} {:>6}  description",
+             "preset", "parts", "groups", "rounds");
+    for preset in &PRESETS {
+        println!("{:<11} {:>5} {:>6} {:>6}  {}",
+                 preset.name, preset.num_participants, preset.num_groups,
+                 preset.best_known, preset.description);
+    }
+}
 
+/// Runs the bench command on the named instances of the catalog, or all of
+/// them. Returns the exit code.
+fn run_bench(names: &[String], opts: &Options) -> i32 {
+    // Times in seconds, or 'none' if no run found a schedule.
+    let secs = |time: Option<Duration>, none: &str| match time {
+        Some(time) => format!("{:.3}", time.as_secs_f64()),
+        None       => none.to_string(),
+    };
+    if opts.csv {
+        println!("instance,participants,groups,rounds,attempts,runs,solved,\
+                  success_rate,median_secs,p95_secs");
+    } else {
+        println!("{:<12} {:>5} {:>6} {:>6} {:>9} {:>5} {:>7} {:>7} {:>9} \
+                  {:>9}",
+                 "instance", "parts", "groups", "rounds", "attempts", "runs",
+                 "solved", "success", "median(s)", "p95(s)");
+    }
+    for inst in CATALOG.iter()
+                       .filter(|inst| names.is_empty() 
+                                      || names.iter().any(|n| n == inst.name)) {
+        let mut inst = *inst;
+        if opts.set_attempts {
+            inst.problem.num_attempts = opts.n_attempts;
+        }
+        let result = bench::run(&inst, opts.runs, opts.seed.unwrap_or(1),
+                                |problem| {
+            if opts.algorithm == Algorithm::Regroup {
+                new_solver(problem, &None, opts).solve()
+            } else {
+                opts.algorithm.solve_with(problem, |_| {})
+            }
+        });
+        let p = &inst.problem;
+        if opts.csv {
+            println!("{},{},{},{},{},{},{},{:.3},{},{}",
+                     inst.name, p.num_participants, p.num_groups, p.num_rounds,
+                     p.num_attempts, result.runs, result.solved(),
+                     result.success_rate(), 
+                     secs(result.median(), ""), secs(result.p95(), ""));
+        } else {
+            println!("{:<12} {:>5} {:>6} {:>6} {:>9} {:>5} {:>7} {:>6.0}% \
+                      {:>9} {:>9}",
+                     inst.name, p.num_participants, p.num_groups,
+                     p.num_rounds, p.num_attempts, result.runs,
+                     result.solved(), result.success_rate() * 100.0,
+                     secs(result.median(), "-"), secs(result.p95(), "-"));
+        }
+    }
+    0
+}
 
+/// Reads the starting rounds in 'path' and checks them against the problem.
+fn read_start(path: &str, problem: &Problem) -> Result<Schedule, String> {
+    let start = read_input(path)?.parse::<Schedule>()?
+                                 .with_parameters(problem.num_participants,
+                                                  problem.group_size());
+    if start.num_rounds() > problem.num_rounds {
+        return Err(format!("it has {} rounds, more than the {} wanted.",
+                           start.num_rounds(), problem.num_rounds));
+    }
+    if start.rounds().iter().any(|round| round.len() != problem.num_groups) {
+        return Err(format!("its rounds don't all have {} groups.",
+                           problem.num_groups));
+    }
+    match verify(&start).first() {
+        Some(violation) => Err(violation.to_string()),
+        None            => Ok(start),
+    }
+}
 
-
-
-
-
-
-
-
-
-
-
+/// Runs the sat-encode and sat-decode commands. Returns the exit code.
+fn run_sat(problem: Problem, opts: &Options) -> i32 {
+    let encoder = SatEncoder { problem,
+                               forbidden : opts.forbidden.clone(),
+                               pins      : opts.pins.clone(),
+                               symmetry  : opts.symmetry };
+    if let Err(msg) = encoder.validate() {
+        println!("{}", &msg);
+        return 2;
+    }
+    match &opts.command {
+        Command::SatDecode(path) => {
+            match read_input(path).and_then(|text| encoder.decode(&text)) {
+                Ok(schedule) => {
+                    println!("{}", schedule);
+                    report_violations(&schedule)
+                },
+                Err(msg) => {
+                    println!("Can't read model from {}: {}", path, msg);
+                    2
+                },
+            }
+        },
+        _ => {
+            print!("{}", encoder.encode());
+            0
+        },
+    }
+}
@@ -0,0 +1,69 @@
+use crate::participant::*;
+use crate::participant_set::*;
+use crate::schedule::*;
+
+/// Who met whom in a schedule, and in which round.
+/// Participants are given by index, as in Schedule, and the schedule can have
+/// no more than `MAX_PARTICIPANTS`. A pair that met more than once, which a
+/// valid schedule doesn't have, is taken to have met in the first of the
+/// rounds.
+#[derive(Clone)]
+pub struct Meetings {
+    num_participants : usize,
+    /// The round each pair met in, by p * num_participants + q.
+    rounds           : Vec<Option<usize>>,
+    /// The participants each participant met.
+    acquaintances    : Vec<ParticipantSet>,
+}
+impl Meetings {
+    /// Collects the meetings of the schedule.
+    pub fn new(schedule: &Schedule) -> Self {
+        let n_parts           = schedule.num_participants();
+        let mut rounds        = vec![None; n_parts * n_parts];
+        let mut acquaintances = vec![ParticipantSet::new(); n_parts];
+
+        for (round_i, round) in schedule.rounds().iter().enumerate() {
+            for group in round {
+                for &p in group.iter().filter(|&&p| p < n_parts) {
+                    for &q in group.iter().filter(|&&q| q < n_parts) {
+                        if p == q { continue; }
+                        acquaintances[p].add(HParticipant { idx: q });
+                        rounds[p * n_parts + q].get_or_insert(round_i);
+                    }
+                }
+            }
+        }
+        Meetings { num_participants: n_parts, rounds, acquaintances }
+    }
+    /// Returns the number of participants.
+    pub fn num_participants(&self) -> usize {
+        self.num_participants
+    }
+    /// Returns the index of the round 'p' and 'q' met in, if they met.
+    pub fn round(&self, p: usize, q: usize) -> Option<usize> {
+        self.rounds[p * self.num_participants + q]
+    }
+    /// Returns the participants 'p' met.
+    pub fn acquaintances(&self, p: usize) -> &ParticipantSet {
+        &self.acquaintances[p]
+    }
+    /// Returns the pairs that never met, each with the lower index first, in
+    /// increasing order.
+    pub fn never_met(&self) -> Vec<(usize, usize)> {
+        let mut everyone = ParticipantSet::new();
+        for idx in 0..self.num_participants {
+            everyone.add(HParticipant { idx });
+        }
+        let mut pairs = vec![];
+        for (p, acquaintances) in self.acquaintances.iter().enumerate() {
+            let mut strangers = everyone;
+            strangers.remove_set(acquaintances);
+            pairs.extend(strangers.iter()
+                                  .map(|hq| hq.idx)
+                                  .filter(|&q| q > p)
+                                  .map(|q| (p, q)));
+        }
+        pairs.sort_unstable();
+        pairs
+    }
+}
@@ -0,0 +1,153 @@
+
+use crate::field::*;
+use crate::schedule::*;
+
+/// A known pair of orthogonal Latin squares of order 10, where MacNeish's
+/// product construction only gives one square.
+const ORDER_10: [[[usize; 10]; 10]; 2] = [
+    [[5, 8, 9, 3, 0, 4, 1, 6, 2, 7], [8, 5, 4, 9, 6, 7, 2, 3, 1, 0],
+     [2, 1, 5, 7, 8, 3, 6, 9, 0, 4], [4, 2, 8, 1, 3, 5, 7, 0, 6, 9],
+     [0, 4, 1, 5, 9, 6, 3, 7, 8, 2], [6, 9, 7, 4, 2, 8, 0, 1, 5, 3],
+     [9, 0, 3, 6, 4, 1, 8, 2, 7, 5], [7, 6, 2, 8, 5, 0, 9, 4, 3, 1],
+     [1, 3, 0, 2, 7, 9, 5, 8, 4, 6], [3, 7, 6, 0, 1, 2, 4, 5, 9, 8]],
+    [[5, 4, 7, 1, 8, 3, 6, 2, 9, 0], [0, 2, 4, 3, 6, 9, 1, 7, 8, 5],
+     [2, 5, 1, 6, 3, 8, 9, 0, 4, 7], [6, 3, 8, 0, 5, 4, 2, 9, 7, 1],
+     [7, 1, 9, 8, 2, 5, 0, 3, 6, 4], [1, 8, 5, 9, 7, 2, 3, 4, 0, 6],
+     [9, 6, 2, 4, 0, 7, 5, 8, 1, 3], [8, 0, 6, 7, 9, 1, 4, 5, 3, 2],
+     [3, 9, 0, 5, 4, 6, 7, 1, 2, 8], [4, 7, 3, 2, 1, 0, 8, 6, 5, 9]],
+];
+
+/// A Latin square, indexed by row and then column.
+pub type LatinSquare = Vec<Vec<usize>>;
+
+/// A set of mutually orthogonal Latin squares.
+/// Any two of the squares are orthogonal: laid over each other, every pair of
+/// symbols occurs in exactly one cell. For k squares of order k, the k * k
+/// participants are the cells, and the rows, the columns and the cells
+/// holding each symbol of each square group them k at a time, with no two
+/// meeting twice. The rows and columns give two rounds and each square one
+/// more, so a set of k - 1 squares gives the k + 1 rounds of a perfect
+/// schedule.
+#[derive(Clone, Debug)]
+pub struct Mols {
+    order   : usize,
+    squares : Vec<LatinSquare>,
+}
+impl Mols {
+    /// Returns the largest set of squares of the order that can be built:
+    /// - For a prime power q, the q - 1 squares a * x + y over GF(q), for
+    ///   each nonzero a.
+    /// - For other orders, MacNeish's product of the sets for the prime power
+    ///   factors, as many squares as the smallest set has.
+    /// - The known pair of order 10.
+    ///
+    /// Every order of 2 or more has at least one square.
+    pub fn new(order: usize) -> Self {
+        let mut squares = vec![];
+        if order == 10 {
+            squares = ORDER_10.iter()
+                              .map(|sq| sq.iter().map(|row| row.to_vec())
+                                                 .collect())
+                              .collect();
+        } else if let Some(field) = Field::new(order) {
+            squares = (1..order).map(|a| {
+                (0..order).map(|x| {
+                    (0..order).map(|y| field.add(field.mul(a, x), y))
+                              .collect()
+                })
+                .collect()
+            })
+            .collect();
+        } else if order > 1 {
+            let p     = smallest_factor(order);
+            let mut q = p;
+            while (order / q).is_multiple_of(p) {
+                q *= p;
+            }
+            squares = product(&Mols::new(q), &Mols::new(order / q));
+        }
+        if squares.is_empty() && order > 1 {
+            let cyclic = (0..order).map(|x| {
+                (0..order).map(|y| (x + y) % order).collect()
+            })
+            .collect();
+            squares.push(cyclic);
+        }
+        Mols { order, squares }
+    }
+    /// Returns the order of the squares.
+    pub fn order(&self) -> usize {
+        self.order
+    }
+    /// Returns the squares.
+    pub fn squares(&self) -> &[LatinSquare] {
+        &self.squares
+    }
+    /// Returns the number of rounds the squares give.
+    pub fn num_rounds(&self) -> usize {
+        if self.order > 1 { self.squares.len() + 2 } else { 0 }
+    }
+    /// Returns the schedule of the first 'n_rounds' rounds the squares give,
+    /// or None if they give fewer. The participant in row x and column y is
+    /// x * order + y. The rounds group the rows, then the columns, then the
+    /// cells with the same symbol in each square in turn.
+    pub fn schedule(&self, n_rounds: usize) -> Option<Schedule> {
+        let k = self.order;
+        if n_rounds > self.num_rounds() {
+            return None;
+        }
+        let mut rvec = vec![(0..k).map(|x| (0..k).map(|y| x * k + y)
+                                                 .collect())
+                                  .collect::<Vec<_>>(),
+                            (0..k).map(|y| (0..k).map(|x| x * k + y)
+                                                 .collect())
+                                  .collect::<Vec<_>>()];
+        for square in &self.squares {
+            let mut gvec = vec![vec![]; k];
+            for x in 0..k {
+                for y in 0..k {
+                    gvec[square[x][y]].push(x * k + y);
+                }
+            }
+            rvec.push(gvec);
+        }
+        rvec.truncate(n_rounds);
+        Some(Schedule::new(k * k, k, rvec))
+    }
+}
+
+/// Indicates whether the two Latin squares of the same order are orthogonal.
+pub fn is_orthogonal(a: &LatinSquare, b: &LatinSquare) -> bool {
+    let k        = a.len();
+    let mut seen = vec![false; k * k];
+    for x in 0..k {
+        for y in 0..k {
+            let pair = a[x][y] * k + b[x][y];
+            if seen[pair] {
+                return false;
+            }
+            seen[pair] = true;
+        }
+    }
+    true
+}
+
+/// Returns the smallest factor of 'n' greater than 1.
+fn smallest_factor(n: usize) -> usize {
+    (2..=n).find(|&d| n.is_multiple_of(d)).unwrap()
+}
+
+/// Returns MacNeish's product of the two sets of squares. The cell in row
+/// (x1, x2) and column (y1, y2) of the product of squares A and B holds
+/// (A[x1][y1], B[x2][y2]), each pair numbered as a two digit number.
+fn product(a: &Mols, b: &Mols) -> Vec<LatinSquare> {
+    let (m, n) = (a.order, b.order);
+    a.squares.iter().zip(&b.squares).map(|(sa, sb)| {
+        (0..m * n).map(|x| {
+            (0..m * n).map(|y| sa[x / n][y / n] * n + sb[x % n][y % n])
+                      .collect()
+        })
+        .collect()
+    })
+    .collect()
+}
@@ -9,7 +9,7 @@ pub const HPARTICIPANT_NULL: HParticipant = HParticipant { idx: usize::MAX };
 
 #[macro_export]
 macro_rules! shuffle {
-    ( $ex:expr ) => { $ex.shuffle(&mut rand::thread_rng()) };
+    ( $ex:expr ) => { $crate::rng::with_rng(|rng| $ex.shuffle(rng)) };
 }
 
 /// Participant handle.
@@ -41,14 +41,16 @@ struct Participant {
 /// The public interface for the crate.
 /// The Participants object maintains the Participants and associates them with
 /// their handles.
+/// It also keeps a scratch buffer so regrouping doesn't allocate.
 #[derive(Default)]
 pub struct Participants {
     next_idx : usize,
     insts    : Vec<Participant>,
+    gscratch : Vec<HGroup>,
 }
 impl Participants {
     pub fn new() -> Self {
-        Participants { next_idx: 0, insts: vec![] }
+        Participants { next_idx: 0, insts: vec![], gscratch: vec![] }
     }
     pub fn hcalloc(&mut self, num: usize) -> Vec<HParticipant> {
         let mut handles = vec![];
@@ -157,7 +159,7 @@ impl Participants {
         // Note: updates the sets of the particpant and other participants, but
         //       not the group's set.         
         let oms = groups.member_set(hg);
-        self.mget(hp).acquaintances.add_set(&oms);
+        self.mget(hp).acquaintances.add_set(oms);
         
         for hop in oms.iter() {
             if hop != hp {
@@ -183,16 +185,19 @@ impl Participants {
         groups.member_set(hg).common(&self.get(hp).acquaintances).to_handle()
     }
     /// Attempts to join the Participant to the Group.
-    /// If successful, `true` is returned; `false` otherwise.
+    /// If successful, `true` is returned; `false` otherwise. The index of the
+    /// Group's Round is updated.
     pub fn try_join(&mut self, 
                     hp      : HParticipant, 
                     hg      : HGroup, 
+                    rounds  : &mut Rounds,
                     groups  : &mut Groups    ) -> bool {
                     
         if hg != HGROUP_NULL && 
            !self.is_acquainted(hp, hg, groups) && !groups.full(hg) {
            
             groups.add(hg, hp);
+            rounds.joined(hp, hg);
             self.acquaint_group(hp, hg, groups);
             self.mget(hp).group = hg;
             true
@@ -207,26 +212,27 @@ impl Participants {
     pub fn try_join_groups(&mut self,
                            hp       : HParticipant,
                            hr       : HRound,
-                           rounds   : &Rounds,
+                           rounds   : &mut Rounds,
                            groups   : &mut Groups    ) -> bool {
                            
-        let gv = rounds.groups(hr);
-        //shuffle!(gv);
-        for &hg in gv {
-            if self.try_join(hp, hg, groups) {
+        for i in 0..rounds.groups(hr).len() {
+            let hg = rounds.groups(hr)[i];
+            if self.try_join(hp, hg, rounds, groups) {
                 return true;
             }
         }
         false
     }
-    /// Removes the Participant from the Group.
+    /// Removes the Participant from the Group, and from the index of the
+    /// Group's Round.
     pub fn leave_group(&mut self,
                        hp       : HParticipant,
                        hg       : HGroup,
+                       rounds   : &mut Rounds,
                        groups   : &mut Groups ) {
                        
         let group_set = groups.member_set(hg);
-        self.mget(hp).acquaintances.remove_set(&group_set);
+        self.mget(hp).acquaintances.remove_set(group_set);
 
         for hop in groups.member_set(hg).iter() {
             if hop != hp {
@@ -234,6 +240,7 @@ impl Participants {
             }
         }
         groups.remove(hg, hp);
+        rounds.left(hp, hg);
 
         self.mget(hp).group = HGROUP_NULL;
     }
@@ -243,20 +250,25 @@ impl Participants {
     /// In the case where the participant traded groups with another, 
     /// Ok(<p-handle>) is returned with the other participant's handle it traded 
     /// with. In the case where no regroup was possible, Err(()) is returned.
+    #[allow(clippy::result_unit_err)]
     pub fn try_regroup(&mut self,
                        hp       : HParticipant,
                        hr       : HRound,
-                       rounds   : &Rounds,
+                       rounds   : &mut Rounds,
                        groups   : &mut Groups    ) -> Result<HParticipant,()> {
                        
         let mut result = Err(());
-        let     hg     = rounds.participant_group(hr, hp, groups);
+        let     hg     = rounds.participant_group(hr, hp);
 
         if hg == HGROUP_NULL {
             return result;
         }
         
-        let mut gvec = rounds.groups(hr).clone();
+        // Shuffle the Round's groups in the scratch buffer, which is taken
+        // for the loop and put back after.
+        let mut gvec = std::mem::take(&mut self.gscratch);
+        gvec.clear();
+        gvec.extend_from_slice(rounds.groups(hr));
         shuffle!(gvec);
         
         'outer: for &hog in &gvec {
@@ -272,10 +284,10 @@ impl Participants {
                 
                 if o_num_acq == 1 { 
                     // hop's acquaintance in hp's Group will be hp.
-                    self.leave_group( hp,  hg, groups);
-                    self.leave_group(hop, hog, groups);
-                    self   .try_join( hp, hog, groups);
-                    self   .try_join(hop,  hg, groups);
+                    self.leave_group( hp,  hg, rounds, groups);
+                    self.leave_group(hop, hog, rounds, groups);
+                    self   .try_join( hp, hog, rounds, groups);
+                    self   .try_join(hop,  hg, rounds, groups);
                     
                     result = Ok(hop);
                     break 'outer;
@@ -284,22 +296,23 @@ impl Participants {
                 for hop in groups.member_set(hog).iter() {
                     let o_num_acq = self.num_acquaint_group(hop, hg, groups);
                     if o_num_acq == 0 {
-                        self.leave_group( hp,  hg, groups);
-                        self.leave_group(hop, hog, groups);
-                        self   .try_join( hp, hog, groups);
-                        self   .try_join(hop,  hg, groups);
+                        self.leave_group( hp,  hg, rounds, groups);
+                        self.leave_group(hop, hog, rounds, groups);
+                        self   .try_join( hp, hog, rounds, groups);
+                        self   .try_join(hop,  hg, rounds, groups);
                         
                         result = Ok(hop);
                         break 'outer;
                     }
                 }
             } else {
-                self.leave_group(hp,  hg, groups);
-                self   .try_join(hp, hog, groups);
+                self.leave_group(hp,  hg, rounds, groups);
+                self   .try_join(hp, hog, rounds, groups);
                 result = Ok(HPARTICIPANT_NULL);
                 break 'outer;
             }
         }
+        self.gscratch = gvec;
         result  
     }
 }
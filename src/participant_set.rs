@@ -8,6 +8,9 @@ type  SetType                = u128;
 const SET_MASK_BIT : SetType = (SetType::MAX >> 1) + 1;
 const N_SET_BITS   : u32     = 128;
 
+/// The most participants a set, and therefore a problem, can hold.
+pub const MAX_PARTICIPANTS: usize = N_SET_BITS as usize;
+
 
 /// Represents a set of Participants.
 /// Internally this is implemented as a bitfield where each participant in the
@@ -44,7 +47,7 @@ impl ParticipantSet {
     pub fn remove_set(&mut self, other: &ParticipantSet) {
         self.value ^= other.value;
     }
-    pub fn to_string(&self, parts: &Participants) -> String {
+    pub fn to_string(self, parts: &Participants) -> String {
         let mut p_strs = vec![];
         let mut hp_vec = ParticipantSetIter::get_vec(self.value);
         hp_vec.sort_by_key(|hp| hp.idx);
@@ -88,7 +91,7 @@ impl ParticipantSet {
         ParticipantSet { value: self.value & other.value }
     }
     /// If there's only one participant in the set, its handle is returned.
-    pub (crate) fn to_handle(&self) -> HParticipant {
+    pub (crate) fn to_handle(self) -> HParticipant {
         debug_assert!(self.value.count_ones() == 1);
         let lz = self.value.leading_zeros();
         HParticipant { idx: ((N_SET_BITS - 1) - lz) as usize }
@@ -0,0 +1,74 @@
+
+use crate::solver::*;
+
+/// A well-known problem instance.
+#[derive(Copy, Clone, Debug)]
+pub struct Preset {
+    /// The name the preset is picked by.
+    pub name             : &'static str,
+    pub num_participants : usize,
+    pub num_groups       : usize,
+    /// The most rounds a schedule is known to have.
+    pub best_known       : usize,
+    /// A line about the problem.
+    pub description      : &'static str,
+}
+impl Preset {
+    /// Returns the problem of the preset for its best known number of rounds.
+    pub fn problem(&self, num_attempts: usize) -> Problem {
+        Problem { num_participants : self.num_participants,
+                  num_groups       : self.num_groups,
+                  num_rounds       : self.best_known,
+                  num_attempts }
+    }
+}
+
+/// The built-in presets.
+pub const PRESETS: [Preset; 9] = [
+    Preset { name: "nine", num_participants: 9, num_groups: 3,
+             best_known: 4,
+             description: "9 in groups of 3, the affine plane of order 3." },
+    Preset { name: "dozen", num_participants: 12, num_groups: 4,
+             best_known: 4,
+             description: "12 in groups of 3. No schedule has more rounds." },
+    Preset { name: "kirkman", num_participants: 15, num_groups: 5,
+             best_known: 7,
+             description: "Kirkman's schoolgirls, walking three abreast." },
+    Preset { name: "sixteen", num_participants: 16, num_groups: 4,
+             best_known: 5,
+             description: "16 in groups of 4, the affine plane of order 4." },
+    Preset { name: "kts21", num_participants: 21, num_groups: 7,
+             best_known: 10,
+             description: "21 in groups of 3, a Kirkman triple system." },
+    Preset { name: "kts27", num_participants: 27, num_groups: 9,
+             best_known: 13,
+             description: "27 in groups of 3, the affine geometry AG(3, 3)." },
+    Preset { name: "golf32", num_participants: 32, num_groups: 8,
+             best_known: 10,
+             description: "The social golfer problem, 32 golfers in fours." },
+    Preset { name: "chess8", num_participants: 8, num_groups: 4,
+             best_known: 7,
+             description: "A round robin of 8 players in pairs." },
+    Preset { name: "squares100", num_participants: 100, num_groups: 10,
+             best_known: 4,
+             description: "100 in groups of 10, from two orthogonal squares." },
+];
+
+/// Well-known problems that have no preset, with why. Their schedules repeat
+/// pairs, which the searches and constructions never do.
+pub const UNMODELED: [(&str, &str); 1] = [
+    ("sylvester", "Sylvester's problem has every pair meet once a week for 13 \
+                   weeks, but schedules here never repeat a pair. Use \
+                   --preset kirkman for a single week."),
+];
+
+/// Returns why the well-known problem named 'name' has no preset, if it's one
+/// of `UNMODELED`.
+pub fn unmodeled(name: &str) -> Option<&'static str> {
+    UNMODELED.iter().find(|(n, _)| *n == name).map(|(_, why)| *why)
+}
+
+/// Returns the preset named 'name', if there is one.
+pub fn find(name: &str) -> Option<&'static Preset> {
+    PRESETS.iter().find(|preset| preset.name == name)
+}
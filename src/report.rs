@@ -0,0 +1,490 @@
+use std::fmt::{self, Write};
+use crate::itinerary::*;
+use crate::meetings::*;
+use crate::schedule::*;
+use crate::solver::*;
+
+/// The version of the JSON schema written by `Report::to_json()`. It's only
+/// raised by changes that could break a reader, not by added fields.
+pub const JSON_VERSION: usize = 1;
+
+/// How a run ended.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Status {
+    /// A search found a schedule.
+    Solved,
+    /// The schedule was built from a known design, without a search.
+    Constructed,
+    /// The search ran out of attempts, and the schedule is its best.
+    Unsolved,
+    /// The search was stopped before the attempts ran out, and the schedule
+    /// is its best.
+    Stopped,
+    /// The exact search showed that no schedule exists.
+    Infeasible,
+}
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Status::Solved      => write!(f, "solved"),
+            Status::Constructed => write!(f, "constructed"),
+            Status::Unsolved    => write!(f, "unsolved"),
+            Status::Stopped     => write!(f, "stopped"),
+            Status::Infeasible  => write!(f, "infeasible"),
+        }
+    }
+}
+
+/// A schedule along with what's known of how it was found, for writing in
+/// formats other programs read.
+///
+/// The JSON written by `to_json()` is an object with these fields. Fields
+/// without a value are `null`. Participant and group ids are those of the
+/// printed schedules: participants from 1, and groups from 1 in the first
+/// round, carrying on through the rounds after it.
+///
+/// - `version`: `JSON_VERSION`.
+/// - `status`: how the run ended, as written by `Status`.
+/// - `method`: the algorithm, or `construction`.
+/// - `design`: the design a constructed schedule came from.
+/// - `problem`: `participants`, `groups`, `rounds` and `group_size`.
+/// - `metrics`: `placements`, `max_placements`, `attempts`, `seconds` and
+///   `stats`, the counts of `SolverStats` under their field names.
+/// - `rounds`: for each round, its `round` number, its `bye`, the id of who
+///   sits it out, if anyone, and its `groups`. Each group has its `group` id
+///   and its `members`, each with an `id` and a `name`.
+///
+/// The CSV of `to_csv()` has a row for each placement, and that of
+/// `to_csv_wide()` a row for each participant. Groups are given by their
+/// label, as in the printed schedules, such as `Group_6`.
+///
+/// The `itinerary_` methods write the schedule by participant instead, with
+/// their group and group-mates in each round. See `Itinerary`. The
+/// `meetings_` methods write the round each pair met in. See `Meetings`.
+#[derive(Clone, Debug)]
+pub struct Report<'a> {
+    pub problem  : Problem,
+    pub schedule : &'a Schedule,
+    pub status   : Status,
+    pub method   : String,
+    pub design   : Option<String>,
+    /// The names of the participants, by index. Participants past the end
+    /// have no name.
+    pub names    : &'a [String],
+    pub attempts : Option<usize>,
+    pub seconds  : Option<f64>,
+    pub stats    : Option<SolverStats>,
+}
+impl<'a> Report<'a> {
+    /// Creates a Report of the schedule with no names or metrics.
+    pub fn new(problem  : Problem,
+               schedule : &'a Schedule,
+               status   : Status,
+               method   : &str    ) -> Self {
+        Report { problem, schedule, status, method: method.to_string(),
+                 design: None, names: &[], attempts: None, seconds: None,
+                 stats: None }
+    }
+    /// Returns the name of participant 'p', if it has one.
+    pub fn name(&self, p: usize) -> Option<&str> {
+        self.names.get(p).map(String::as_str)
+    }
+    /// Returns the label of the group with id 'group_id'.
+    pub fn group_label(group_id: usize) -> String {
+        format!("Group_{}", group_id)
+    }
+    /// Returns the report as CSV with the columns round, group, participant
+    /// and name, and a row for each participant placed in each round. Who
+    /// sits out a round is in the group `Bye`.
+    pub fn to_csv(&self) -> String {
+        let mut s = String::from("round,group,participant,name\n");
+        let mut group_id = 0;
+        for (round_i, round) in self.schedule.rounds().iter().enumerate() {
+            for group in round {
+                group_id += 1;
+                let label = Report::group_label(group_id);
+                for &p in group {
+                    s += &format!("{},{},{},{}\n", round_i + 1, label, p + 1,
+                                  csv_str(self.name(p)));
+                }
+            }
+            if let Some(p) = self.schedule.bye(round_i) {
+                s += &format!("{},Bye,{},{}\n", round_i + 1, p + 1,
+                              csv_str(self.name(p)));
+            }
+        }
+        s
+    }
+    /// Returns the report as CSV with a row for each participant, giving
+    /// their id and name, and a column for each round with their group in
+    /// it. The group is `Bye` for who sits out the round, and empty for a
+    /// participant who wasn't placed.
+    pub fn to_csv_wide(&self) -> String {
+        let n_parts  = self.schedule.num_participants();
+        let n_rounds = self.schedule.num_rounds();
+        let mut cells = vec![vec![String::new(); n_rounds]; n_parts];
+        let mut group_id = 0;
+        for (round_i, round) in self.schedule.rounds().iter().enumerate() {
+            for group in round {
+                group_id += 1;
+                for &p in group.iter().filter(|&&p| p < n_parts) {
+                    cells[p][round_i] = Report::group_label(group_id);
+                }
+            }
+            if let Some(p) = self.schedule.bye(round_i) {
+                cells[p][round_i] = "Bye".to_string();
+            }
+        }
+        let mut s = String::from("participant,name");
+        for round_i in 0..n_rounds {
+            s += &format!(",Round_{}", round_i + 1);
+        }
+        s.push('\n');
+        for (p, row) in cells.iter().enumerate() {
+            s += &format!("{},{},{}\n", p + 1, csv_str(self.name(p)),
+                          row.join(","));
+        }
+        s
+    }
+    /// Returns the participant's name, or their id if they have no name.
+    fn who(&self, p: usize) -> String {
+        match self.name(p) {
+            Some(name) => name.to_string(),
+            None       => (p + 1).to_string(),
+        }
+    }
+    /// Returns the label of the participant's stop in the 'round_i'th round:
+    /// their group's, `Bye` if they sit the round out, or None if they
+    /// weren't placed.
+    fn stop_label(&self, p: usize, round_i: usize, stop: &Stop)
+        -> Option<String>
+    {
+        match stop.group {
+            Some(group_id) => Some(Report::group_label(group_id)),
+            None if self.schedule.bye(round_i) == Some(p) => {
+                Some("Bye".to_string())
+            },
+            None => None,
+        }
+    }
+    /// Returns the itineraries as text, a list for each participant of their
+    /// group and group-mates in each round. Participants with names are
+    /// given by name.
+    pub fn itinerary_text(&self) -> String {
+        let mut s = String::new();
+        for (i, itinerary) in Itinerary::all(self.schedule).iter()
+                                                            .enumerate() {
+            let p = itinerary.participant;
+            if i > 0 {
+                s.push('\n');
+            }
+            match self.name(p) {
+                Some(name) => s += &format!("Participant {} ({}):\n", p + 1,
+                                            name),
+                None       => s += &format!("Participant {}:\n", p + 1),
+            }
+            for (round_i, stop) in itinerary.stops.iter().enumerate() {
+                let mates = stop.mates.iter()
+                                      .map(|&m| self.who(m))
+                                      .collect::<Vec<_>>();
+                let place = match self.stop_label(p, round_i, stop) {
+                    Some(label) if !mates.is_empty() => {
+                        format!("{} with {}", label, mates.join(", "))
+                    },
+                    Some(label) => label,
+                    None        => "not placed".to_string(),
+                };
+                s += &format!("    Round_{:<2}: {}\n", round_i + 1, place);
+            }
+        }
+        s
+    }
+    /// Returns the itineraries as CSV with the columns participant, name,
+    /// round, group, mates and mate_names, and a row for each participant in
+    /// each round. The mates are given by id, separated by spaces, and by
+    /// name, separated by semicolons.
+    pub fn itinerary_csv(&self) -> String {
+        let mut s = String::from("participant,name,round,group,mates,\
+                                  mate_names\n");
+        for itinerary in Itinerary::all(self.schedule) {
+            let p = itinerary.participant;
+            for (round_i, stop) in itinerary.stops.iter().enumerate() {
+                let ids   = stop.mates.iter()
+                                      .map(|&m| (m + 1).to_string())
+                                      .collect::<Vec<_>>();
+                let names = stop.mates.iter()
+                                      .filter_map(|&m| self.name(m))
+                                      .collect::<Vec<_>>();
+                let label = self.stop_label(p, round_i, stop);
+                s += &format!("{},{},{},{},{},{}\n",
+                              p + 1, csv_str(self.name(p)), round_i + 1,
+                              label.unwrap_or_default(), ids.join(" "),
+                              csv_str(Some(&names.join("; "))));
+            }
+        }
+        s
+    }
+    /// Returns the itineraries as pretty printed JSON: an object with the
+    /// `version`, `status` and `participants`. Each participant has their
+    /// `id`, `name` and `rounds`, and each round its `round` number, the
+    /// `group` id and `label`, whether it's a `bye`, and the `mates`, each
+    /// with an `id` and a `name`. The group is `null` for a bye, and the
+    /// label too for a participant who wasn't placed.
+    pub fn itinerary_json(&self) -> String {
+        let mut s = String::new();
+        self.write_itinerary_json(&mut s).unwrap();
+        s
+    }
+    fn write_itinerary_json(&self, s: &mut String) -> fmt::Result {
+        let itineraries = Itinerary::all(self.schedule);
+        writeln!(s, "{{")?;
+        writeln!(s, "  \"version\": {},", JSON_VERSION)?;
+        writeln!(s, "  \"status\": \"{}\",", self.status)?;
+        writeln!(s, "  \"participants\": [")?;
+        for (i, itinerary) in itineraries.iter().enumerate() {
+            let p = itinerary.participant;
+            writeln!(s, "    {{")?;
+            writeln!(s, "      \"id\": {},", p + 1)?;
+            writeln!(s, "      \"name\": {},", json_str(self.name(p)))?;
+            writeln!(s, "      \"rounds\": [")?;
+            let n_stops = itinerary.stops.len();
+            for (round_i, stop) in itinerary.stops.iter().enumerate() {
+                let label = self.stop_label(p, round_i, stop);
+                let bye   = stop.group.is_none() && label.is_some();
+                let mates = stop.mates.iter()
+                                      .map(|&m| format!("{{\"id\": {}, \
+                                                         \"name\": {}}}",
+                                                        m + 1,
+                                                        json_str(self.name(m))))
+                                      .collect::<Vec<_>>();
+                writeln!(s, "        {{\"round\": {}, \"group\": {}, \
+                             \"label\": {}, \"bye\": {}, \"mates\": [{}]}}{}",
+                         round_i + 1, json_opt(stop.group),
+                         json_str(label.as_deref()), bye, mates.join(", "),
+                         comma(round_i, n_stops))?;
+            }
+            writeln!(s, "      ]")?;
+            writeln!(s, "    }}{}", comma(i, itineraries.len()))?;
+        }
+        writeln!(s, "  ]")?;
+        writeln!(s, "}}")
+    }
+    /// Returns the meetings as a table aligned in columns, with a row and a
+    /// column for each participant, by id, and the round each pair met in
+    /// where they cross, followed by the list of pairs that never met.
+    pub fn meetings_text(&self) -> String {
+        let meetings = Meetings::new(self.schedule);
+        let n_parts  = meetings.num_participants();
+        let width    = n_parts.max(self.schedule.num_rounds())
+                              .to_string()
+                              .len();
+        let mut s = format!("{:>w$}", "", w = width);
+        for q in 0..n_parts {
+            s += &format!(" {:>w$}", q + 1, w = width);
+        }
+        s.push('\n');
+        for p in 0..n_parts {
+            let mut row = format!("{:>w$}", p + 1, w = width);
+            for q in 0..n_parts {
+                let cell = match meetings.round(p, q) {
+                    _ if p == q   => "-".to_string(),
+                    Some(round_i) => (round_i + 1).to_string(),
+                    None          => String::new(),
+                };
+                row += &format!(" {:>w$}", cell, w = width);
+            }
+            s += row.trim_end();
+            s.push('\n');
+        }
+        let never_met = meetings.never_met();
+        s += &format!("\nPairs that never met: {}\n", never_met.len());
+        for (p, q) in never_met {
+            s += &format!("    {} and {}\n", self.who(p), self.who(q));
+        }
+        s
+    }
+    /// Returns the meetings as CSV with a row for each participant, giving
+    /// their id and name, and a column for each participant, headed by their
+    /// id, with the round the two met in. The cell is empty if they never
+    /// met.
+    pub fn meetings_csv(&self) -> String {
+        let meetings = Meetings::new(self.schedule);
+        let n_parts  = meetings.num_participants();
+        let mut s = String::from("participant,name");
+        for q in 0..n_parts {
+            s += &format!(",{}", q + 1);
+        }
+        s.push('\n');
+        for p in 0..n_parts {
+            s += &format!("{},{}", p + 1, csv_str(self.name(p)));
+            for q in 0..n_parts {
+                let round = meetings.round(p, q).map(|round_i| round_i + 1);
+                s += &format!(",{}", round.map_or(String::new(),
+                                                   |r| r.to_string()));
+            }
+            s.push('\n');
+        }
+        s
+    }
+    /// Returns the meetings as pretty printed JSON: an object with the
+    /// `version`, `status`, the `meetings`, a row for each participant with
+    /// the round they met each participant in, or `null`, and the pairs that
+    /// `never_met`, by id.
+    pub fn meetings_json(&self) -> String {
+        let mut s = String::new();
+        self.write_meetings_json(&mut s).unwrap();
+        s
+    }
+    fn write_meetings_json(&self, s: &mut String) -> fmt::Result {
+        let meetings  = Meetings::new(self.schedule);
+        let n_parts   = meetings.num_participants();
+        let never_met = meetings.never_met()
+                                .iter()
+                                .map(|(p, q)| format!("[{}, {}]", p + 1, q + 1))
+                                .collect::<Vec<_>>();
+        writeln!(s, "{{")?;
+        writeln!(s, "  \"version\": {},", JSON_VERSION)?;
+        writeln!(s, "  \"status\": \"{}\",", self.status)?;
+        writeln!(s, "  \"meetings\": [")?;
+        for p in 0..n_parts {
+            let row = (0..n_parts).map(|q| {
+                                      json_opt(meetings.round(p, q)
+                                                       .map(|r| r + 1))
+                                  })
+                                  .collect::<Vec<_>>();
+            writeln!(s, "    [{}]{}", row.join(", "), comma(p, n_parts))?;
+        }
+        writeln!(s, "  ],")?;
+        writeln!(s, "  \"never_met\": [{}]", never_met.join(", "))?;
+        writeln!(s, "}}")
+    }
+    /// Returns the report as pretty printed JSON.
+    pub fn to_json(&self) -> String {
+        let mut s = String::new();
+        self.write_json(&mut s).unwrap();
+        s
+    }
+    fn write_json(&self, s: &mut String) -> fmt::Result {
+        let p        = &self.problem;
+        let seconds  = self.seconds.map(|secs| format!("{:.3}", secs));
+        writeln!(s, "{{")?;
+        writeln!(s, "  \"version\": {},", JSON_VERSION)?;
+        writeln!(s, "  \"status\": \"{}\",", self.status)?;
+        writeln!(s, "  \"method\": {},", json_str(Some(&self.method)))?;
+        writeln!(s, "  \"design\": {},", json_str(self.design.as_deref()))?;
+        writeln!(s, "  \"problem\": {{")?;
+        writeln!(s, "    \"participants\": {},", p.num_participants)?;
+        writeln!(s, "    \"groups\": {},", p.num_groups)?;
+        writeln!(s, "    \"rounds\": {},", p.num_rounds)?;
+        writeln!(s, "    \"group_size\": {}", p.group_size())?;
+        writeln!(s, "  }},")?;
+        writeln!(s, "  \"metrics\": {{")?;
+        writeln!(s, "    \"placements\": {},", self.schedule.num_placements())?;
+        writeln!(s, "    \"max_placements\": {},", p.num_placements())?;
+        writeln!(s, "    \"attempts\": {},", json_opt(self.attempts))?;
+        writeln!(s, "    \"seconds\": {},", json_opt(seconds))?;
+        match &self.stats {
+            Some(stats) => {
+                writeln!(s, "    \"stats\": {{")?;
+                write_stats(s, stats)?;
+                writeln!(s, "    }}")?;
+            },
+            None => writeln!(s, "    \"stats\": null")?,
+        }
+        writeln!(s, "  }},")?;
+        writeln!(s, "  \"rounds\": [")?;
+
+        let mut group_id = 0;
+        let     n_rounds = self.schedule.num_rounds();
+        for (round_i, round) in self.schedule.rounds().iter().enumerate() {
+            let bye = self.schedule.bye(round_i).map(|p| p + 1);
+            writeln!(s, "    {{")?;
+            writeln!(s, "      \"round\": {},", round_i + 1)?;
+            writeln!(s, "      \"bye\": {},", json_opt(bye))?;
+            writeln!(s, "      \"groups\": [")?;
+            for (group_i, group) in round.iter().enumerate() {
+                group_id += 1;
+                let members = group.iter()
+                                   .map(|&p| format!("{{\"id\": {}, \
+                                                      \"name\": {}}}",
+                                                     p + 1,
+                                                     json_str(self.name(p))))
+                                   .collect::<Vec<_>>();
+                writeln!(s, "        {{\"group\": {}, \"members\": [{}]}}{}",
+                         group_id, members.join(", "),
+                         comma(group_i, round.len()))?;
+            }
+            writeln!(s, "      ]")?;
+            writeln!(s, "    }}{}", comma(round_i, n_rounds))?;
+        }
+        writeln!(s, "  ]")?;
+        writeln!(s, "}}")
+    }
+}
+
+/// Writes the fields of the stats object.
+fn write_stats(s: &mut String, stats: &SolverStats) -> fmt::Result {
+    let by_round = stats.restarts_by_round.iter()
+                                          .map(|n| n.to_string())
+                                          .collect::<Vec<_>>();
+    writeln!(s, "      \"attempts\": {},", stats.attempts)?;
+    writeln!(s, "      \"restarts\": {},", stats.restarts)?;
+    writeln!(s, "      \"restarts_by_round\": [{}],", by_round.join(", "))?;
+    writeln!(s, "      \"partial\": {},", stats.partial)?;
+    writeln!(s, "      \"deepest\": {},", stats.deepest)?;
+    writeln!(s, "      \"blocked\": {},", stats.blocked)?;
+    writeln!(s, "      \"regroups\": {},", stats.regroups)?;
+    writeln!(s, "      \"moved\": {},", stats.moved)?;
+    writeln!(s, "      \"swapped\": {},", stats.swapped)?;
+    writeln!(s, "      \"failed\": {}", stats.failed)
+}
+
+/// Returns the separator after item 'i' of 'n'.
+fn comma(i: usize, n: usize) -> &'static str {
+    if i + 1 < n { "," } else { "" }
+}
+
+/// Returns the value as JSON, or `null`.
+fn json_opt<T: fmt::Display>(value: Option<T>) -> String {
+    match value {
+        Some(value) => value.to_string(),
+        None        => "null".to_string(),
+    }
+}
+
+/// Returns the string as a CSV field, quoted if it needs to be, or an empty
+/// field.
+fn csv_str(value: Option<&str>) -> String {
+    match value {
+        Some(value) if value.contains(&[',', '"', '\n', '\r'][..]) => {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        },
+        Some(value) => value.to_string(),
+        None        => String::new(),
+    }
+}
+
+/// Returns the string as a quoted and escaped JSON string, or `null`.
+fn json_str(value: Option<&str>) -> String {
+    let value = match value {
+        Some(value) => value,
+        None        => return "null".to_string(),
+    };
+    let mut s = String::with_capacity(value.len() + 2);
+    s.push('"');
+    for c in value.chars() {
+        match c {
+            '"'  => s.push_str("\\\""),
+            '\\' => s.push_str("\\\\"),
+            '\n' => s.push_str("\\n"),
+            '\r' => s.push_str("\\r"),
+            '\t' => s.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                s.push_str(&format!("\\u{:04x}", c as u32));
+            },
+            c => s.push(c),
+        }
+    }
+    s.push('"');
+    s
+}
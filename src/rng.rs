@@ -0,0 +1,31 @@
+
+use rand::prelude::*;
+use rand::rngs::StdRng;
+use std::cell::RefCell;
+
+thread_local! {
+    /// The generator the searches of each thread draw from. It's seeded from
+    /// the system unless `seed()` is called.
+    static RNG: RefCell<StdRng> = RefCell::new(StdRng::from_entropy());
+}
+
+/// Reseeds the current thread's generator, so the searches it runs after are
+/// repeated exactly by giving the same seed again.
+pub fn seed(seed: u64) {
+    RNG.with(|rng| *rng.borrow_mut() = StdRng::seed_from_u64(seed));
+}
+
+/// Calls 'f' with the current thread's generator and returns its result.
+/// 'f' must not call `with_rng()` itself.
+pub fn with_rng<T, F>(f: F) -> T
+where
+    F: FnOnce(&mut StdRng) -> T,
+{
+    RNG.with(|rng| f(&mut rng.borrow_mut()))
+}
+
+/// Returns a new generator seeded from the current thread's, for searches
+/// that hold on to one for a whole run.
+pub fn fork() -> StdRng {
+    StdRng::seed_from_u64(with_rng(|rng| rng.gen()))
+}
@@ -16,15 +16,20 @@ struct Round {
 
 /// The aggregate class that holds the Round objects and assicates them with
 /// their handles and provides their interface.
+/// It also indexes, for each Round, the Group each Participant is in, which
+/// `Participants::try_join()` and `leave_group()` keep up to date.
 #[derive(Default)]
 pub struct Rounds {
-    next_idx : usize,
-    insts    : Vec<Round>,
+    next_idx     : usize,
+    insts        : Vec<Round>,
+    group_rounds : Vec<usize>,
+    placements   : Vec<Vec<HGroup>>,
 }
 impl Rounds {
     /// Returns the aggregate object for Round's.
     pub fn new() -> Self {
-        Rounds { next_idx: 0, insts: vec![] }
+        Rounds { next_idx: 0, insts: vec![], group_rounds: vec![],
+                 placements: vec![] }
     }
     /// Causes 'num' instances of Round to be created, and returns the handles
     /// for them.
@@ -35,6 +40,7 @@ impl Rounds {
         self.next_idx   = end;
         for i in 0..num {
             self.insts.push(Round { id: i + 1, groups: vec![] });
+            self.placements.push(vec![]);
             handles.push(HRound { idx: i });
         }
         handles
@@ -43,13 +49,27 @@ impl Rounds {
     #[allow(dead_code)]
     pub fn free_all(&mut self) {
         self.insts.clear();
+        self.group_rounds.clear();
+        self.placements.clear();
         self.next_idx = 0;
     }
-    /// Resets the Round's and clears their group lists.
+    /// Resets the Round's and clears their group lists and indexes.
     pub fn reset(&mut self) {
         for round in &mut self.insts {
             round.groups.clear();
         }
+        for placements in &mut self.placements {
+            placements.clear();
+        }
+        self.group_rounds.clear();
+    }
+    /// Takes the Groups out of the Round behind 'hr' and clears its index.
+    pub fn clear(&mut self, hr: HRound) {
+        for &hg in &self.insts[hr.idx].groups {
+            self.group_rounds[hg.idx] = usize::MAX;
+        }
+        self.mget(hr).groups.clear();
+        self.placements[hr.idx].clear();
     }
     /// Returns an immutable reference to the Round associated with 'hr'.
     #[inline]
@@ -66,8 +86,13 @@ impl Rounds {
     pub fn hget(&self, idx: usize) -> HRound {
         HRound { idx }
     }
-    /// Adds the Group, 'hg', to the Round behind 'hr'.
+    /// Adds the Group, 'hg', to the Round behind 'hr'. The Group is expected
+    /// to be empty.
     pub fn add(&mut self, hr: HRound, hg: HGroup) {
+        if self.group_rounds.len() <= hg.idx {
+            self.group_rounds.resize(hg.idx + 1, usize::MAX);
+        }
+        self.group_rounds[hg.idx] = hr.idx;
         self.mget(hr).groups.push(hg);
     }
     /// Adds the slice of Groups to the Round.
@@ -90,17 +115,30 @@ impl Rounds {
         total
     }
     /// Returns the Group handle for the Participant, 'hp', within the Round,
-    /// 'hr'.
-    pub fn participant_group(&self, 
-                             hr     : HRound, 
-                             hp     : HParticipant, 
-                             groups : &Groups       ) -> HGroup {
-        for &hg in self.groups(hr) {
-            if groups.has(hg, hp) {
-                return hg;
+    /// 'hr', or HGROUP_NULL if it isn't in one. This is a lookup in the
+    /// Round's index.
+    #[inline]
+    pub fn participant_group(&self, hr: HRound, hp: HParticipant) -> HGroup {
+        self.placements[hr.idx].get(hp.idx).copied().unwrap_or(HGROUP_NULL)
+    }
+    /// Records in the index that 'hp' joined the Group 'hg'. Groups not in
+    /// any Round aren't indexed.
+    pub(crate) fn joined(&mut self, hp: HParticipant, hg: HGroup) {
+        let r = self.group_rounds.get(hg.idx).copied().unwrap_or(usize::MAX);
+        if r != usize::MAX {
+            let placements = &mut self.placements[r];
+            if placements.len() <= hp.idx {
+                placements.resize(hp.idx + 1, HGROUP_NULL);
             }
+            placements[hp.idx] = hg;
+        }
+    }
+    /// Records in the index that 'hp' left the Group 'hg'.
+    pub(crate) fn left(&mut self, hp: HParticipant, hg: HGroup) {
+        let r = self.group_rounds.get(hg.idx).copied().unwrap_or(usize::MAX);
+        if r != usize::MAX && hp.idx < self.placements[r].len() {
+            self.placements[r][hp.idx] = HGROUP_NULL;
         }
-        HGROUP_NULL
     }
     /// Returns the string representation for the Round, 'hr'.
     pub fn to_string(&self, 
@@ -0,0 +1,277 @@
+
+use std::fmt;
+use crate::schedule::*;
+use crate::solver::*;
+
+/// A formula in conjunctive normal form.
+/// Literals are numbered from 1 as in the DIMACS format, with negative numbers
+/// for negated variables. The Display implementation writes the formula in
+/// DIMACS CNF, ready for an off-the-shelf SAT solver.
+#[derive(Clone, Debug, Default)]
+pub struct Cnf {
+    pub num_vars : usize,
+    pub comments : Vec<String>,
+    pub clauses  : Vec<Vec<i64>>,
+}
+impl Cnf {
+    /// Allocates a new variable and returns its number.
+    fn new_var(&mut self) -> i64 {
+        self.num_vars += 1;
+        self.num_vars as i64
+    }
+    /// Adds a clause.
+    fn add(&mut self, clause: Vec<i64>) {
+        self.clauses.push(clause);
+    }
+    /// Adds clauses allowing at most one of the literals to be true.
+    fn at_most_one(&mut self, lits: &[i64]) {
+        for (i, &a) in lits.iter().enumerate() {
+            for &b in &lits[i + 1..] {
+                self.add(vec![-a, -b]);
+            }
+        }
+    }
+    /// Adds clauses allowing at most 'k' of the literals to be true, using
+    /// Sinz's sequential counter. The counter's register s[i][j] is true when
+    /// at least j + 1 of the first i + 1 literals are true.
+    fn at_most(&mut self, lits: &[i64], k: usize) {
+        if k >= lits.len() {
+            return;
+        }
+        if k == 0 {
+            for &x in lits {
+                self.add(vec![-x]);
+            }
+            return;
+        }
+        let n     = lits.len();
+        let mut s = vec![vec![0; k]; n - 1];
+        for row in &mut s {
+            for reg in row.iter_mut() {
+                *reg = self.new_var();
+            }
+        }
+        self.add(vec![-lits[0], s[0][0]]);
+        for reg in &s[0][1..] {
+            self.add(vec![-reg]);
+        }
+        for i in 1..n - 1 {
+            self.add(vec![-lits[i], s[i][0]]);
+            self.add(vec![-s[i - 1][0], s[i][0]]);
+            for j in 1..k {
+                self.add(vec![-lits[i], -s[i - 1][j - 1], s[i][j]]);
+                self.add(vec![-s[i - 1][j], s[i][j]]);
+            }
+            self.add(vec![-lits[i], -s[i - 1][k - 1]]);
+        }
+        self.add(vec![-lits[n - 1], -s[n - 2][k - 1]]);
+    }
+}
+impl fmt::Display for Cnf {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for comment in &self.comments {
+            writeln!(f, "c {}", comment)?;
+        }
+        writeln!(f, "p cnf {} {}", self.num_vars, self.clauses.len())?;
+        for clause in &self.clauses {
+            for lit in clause {
+                write!(f, "{} ", lit)?;
+            }
+            writeln!(f, "0")?;
+        }
+        Ok(())
+    }
+}
+
+/// Encodes a problem instance as a SAT formula, and decodes the models found
+/// by SAT solvers back into schedules.
+///
+/// Variable x(p, r, g), numbered from 1 by `var()`, is true when participant
+/// 'p' is in group 'g' of round 'r'. These come first, followed by auxiliary
+/// variables for the group sizes and for the rounds in which pairs meet.
+/// The clauses require that
+/// - each participant is in exactly one group per round,
+/// - no group holds more than the group size, which, with everyone placed,
+///   makes every group full,
+/// - no two participants meet in more than one round,
+/// - the forbidden pairs never meet, and
+/// - the pinned participants are in their pinned groups.
+///
+/// With symmetry breaking, the first round is fixed and the participants of
+/// its first group are each put in the group of their own number in the later
+/// rounds. Relabeling participants would break forbidden pairs and pins, so
+/// with forbidden pairs only participant 1 is fixed in the first group of
+/// each round, and with pins nothing is fixed.
+#[derive(Clone, Debug)]
+pub struct SatEncoder {
+    pub problem   : Problem,
+    /// Pairs of participant indices that may not be grouped together.
+    pub forbidden : Vec<(usize, usize)>,
+    /// Participant, round and group indices of fixed placements.
+    pub pins      : Vec<(usize, usize, usize)>,
+    /// Whether to add the symmetry breaking clauses.
+    pub symmetry  : bool,
+}
+impl SatEncoder {
+    /// Creates a SatEncoder for the problem with no forbidden pairs or pins
+    /// and without symmetry breaking.
+    pub fn new(problem: Problem) -> Self {
+        SatEncoder { problem, forbidden: vec![], pins: vec![],
+                     symmetry: false }
+    }
+    /// Returns the variable for participant 'p' being in group 'g' of round
+    /// 'r'.
+    pub fn var(&self, p: usize, r: usize, g: usize) -> i64 {
+        let n_rounds = self.problem.num_rounds;
+        let n_groups = self.problem.num_groups;
+        ((p * n_rounds + r) * n_groups + g + 1) as i64
+    }
+    /// Checks that the forbidden pairs and pins refer to participants, rounds
+    /// and groups of the problem, and that the problem has no bye.
+    pub fn validate(&self) -> Result<(), String> {
+        let n_parts = self.problem.num_participants;
+        if self.problem.has_bye() {
+            return Err("Rounds with a bye can't be encoded.".to_string());
+        }
+        for &(a, b) in &self.forbidden {
+            if a >= n_parts || b >= n_parts || a == b {
+                return Err(format!("Invalid forbidden pair {},{}.",
+                                   a + 1, b + 1));
+            }
+        }
+        for &(p, r, g) in &self.pins {
+            if p >= n_parts || r >= self.problem.num_rounds
+                            || g >= self.problem.num_groups {
+                return Err(format!("Invalid pin {},{},{}.",
+                                   p + 1, r + 1, g + 1));
+            }
+        }
+        Ok(())
+    }
+    /// Builds the formula.
+    pub fn encode(&self) -> Cnf {
+        let n_parts  = self.problem.num_participants;
+        let n_rounds = self.problem.num_rounds;
+        let n_groups = self.problem.num_groups;
+        let size     = self.problem.group_size();
+        let comments = vec![
+            format!("socialx: {} participants, {} groups, {} rounds.",
+                    n_parts, n_groups, n_rounds),
+            "Variable ((p - 1) * rounds + (r - 1)) * groups + g is true when \
+             participant p is in group g of round r.".to_string(),
+        ];
+        let mut cnf  = Cnf { num_vars: n_parts * n_rounds * n_groups,
+                             comments, clauses: vec![] };
+
+        // Each participant is in exactly one group per round.
+        for p in 0..n_parts {
+            for r in 0..n_rounds {
+                let lits = (0..n_groups).map(|g| self.var(p, r, g))
+                                        .collect::<Vec<_>>();
+                cnf.add(lits.clone());
+                cnf.at_most_one(&lits);
+            }
+        }
+        // No group holds more than the group size.
+        for r in 0..n_rounds {
+            for g in 0..n_groups {
+                let lits = (0..n_parts).map(|p| self.var(p, r, g))
+                                       .collect::<Vec<_>>();
+                cnf.at_most(&lits, size);
+            }
+        }
+        // No pair meets in more than one round. m(a, b, r) is forced true when
+        // a and b share a group in round r.
+        for a in 0..n_parts {
+            for b in a + 1..n_parts {
+                let mut meets = vec![];
+                for r in 0..n_rounds {
+                    let m = cnf.new_var();
+                    for g in 0..n_groups {
+                        cnf.add(vec![-self.var(a, r, g),
+                                     -self.var(b, r, g), m]);
+                    }
+                    meets.push(m);
+                }
+                cnf.at_most_one(&meets);
+            }
+        }
+        for &(a, b) in &self.forbidden {
+            for r in 0..n_rounds {
+                for g in 0..n_groups {
+                    cnf.add(vec![-self.var(a, r, g), -self.var(b, r, g)]);
+                }
+            }
+        }
+        for &(p, r, g) in &self.pins {
+            cnf.add(vec![self.var(p, r, g)]);
+        }
+        if self.symmetry && self.pins.is_empty() {
+            if self.forbidden.is_empty() {
+                for p in 0..n_parts {
+                    cnf.add(vec![self.var(p, 0, p / size)]);
+                }
+                for r in 1..n_rounds {
+                    for p in 0..size.min(n_groups) {
+                        cnf.add(vec![self.var(p, r, p)]);
+                    }
+                }
+            } else {
+                for r in 0..n_rounds {
+                    cnf.add(vec![self.var(0, r, 0)]);
+                }
+            }
+        }
+        cnf
+    }
+    /// Reads a model from a SAT solver's output and returns the schedule it
+    /// describes. Both the competition format, with `s` and `v` lines, and
+    /// the plain list of literals MiniSat writes are accepted. Literals of
+    /// the auxiliary variables are ignored. The schedule isn't checked, so
+    /// it should be passed to `verify()`.
+    pub fn decode(&self, model: &str) -> Result<Schedule, String> {
+        let n_parts  = self.problem.num_participants;
+        let n_rounds = self.problem.num_rounds;
+        let n_groups = self.problem.num_groups;
+        let n_x      = (n_parts * n_rounds * n_groups) as i64;
+        let mut rvec = vec![vec![vec![]; n_groups]; n_rounds];
+        let mut n_lits = 0;
+
+        for line in model.lines() {
+            let line = line.trim();
+            if line.starts_with('c') {
+                continue;
+            }
+            if line.contains("UNSAT") {
+                return Err("The solver found the formula unsatisfiable."
+                           .to_string());
+            }
+            if line.starts_with('s') || line.starts_with("SAT") {
+                continue;
+            }
+            let line = line.strip_prefix('v').unwrap_or(line);
+            for tok in line.split_whitespace() {
+                let lit = tok.parse::<i64>()
+                             .map_err(|_| format!("Invalid literal ({}).",
+                                                  tok))?;
+                n_lits += 1;
+                if lit > 0 && lit <= n_x {
+                    let x = (lit - 1) as usize;
+                    let g = x % n_groups;
+                    let r = x / n_groups % n_rounds;
+                    let p = x / n_groups / n_rounds;
+                    rvec[r][g].push(p);
+                }
+            }
+        }
+        if n_lits == 0 {
+            return Err("No model found.".to_string());
+        }
+        for group in rvec.iter_mut().flatten() {
+            group.sort_unstable();
+        }
+        Ok(Schedule::new(n_parts, self.problem.group_size(), rvec))
+    }
+}
+
+
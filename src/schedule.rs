@@ -0,0 +1,206 @@
+
+use std::fmt;
+use std::str::FromStr;
+use crate::canon::*;
+use crate::group::*;
+use crate::round::*;
+
+/// A plain value holding the groupings of every round.
+/// Unlike the handle based aggregates, a Schedule owns its data and can be
+/// kept, compared and printed after the objects it was built from have moved
+/// on to another attempt. Participants are given by their index, which is one
+/// less than the id shown when the schedule is printed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Schedule {
+    num_participants : usize,
+    group_size       : usize,
+    rounds           : Vec<Vec<Vec<usize>>>,
+}
+impl Schedule {
+    /// Creates a Schedule from rounds of groups of participant indices.
+    pub fn new(num_participants : usize,
+               group_size       : usize,
+               rounds           : Vec<Vec<Vec<usize>>>) -> Self {
+        Schedule { num_participants, group_size, rounds }
+    }
+    /// Returns a copy of the schedule for a different number of participants
+    /// and group size. This is used to check a parsed schedule against the
+    /// parameters it was meant for.
+    pub fn with_parameters(&self, 
+                           num_participants : usize, 
+                           group_size       : usize  ) -> Self {
+        Schedule::new(num_participants, group_size, self.rounds.clone())
+    }
+    /// Returns the group size that 'num_groups' groups need to hold everyone,
+    /// or 2 for pairs and a bye.
+    pub fn group_size_for(num_participants: usize, num_groups: usize) -> usize {
+        let num_groups = num_groups.max(1);
+        if num_groups > 1 && num_participants == 2 * num_groups + 1 {
+            2
+        } else {
+            num_participants.div_ceil(num_groups)
+        }
+    }
+    /// Takes a snapshot of the groupings of the Round's, 'hrs'.
+    pub fn from_rounds(hrs              : &[HRound],
+                       rounds           : &Rounds,
+                       groups           : &Groups,
+                       num_participants : usize,
+                       group_size       : usize    ) -> Self {
+        let mut schedule = Schedule::new(num_participants, group_size, vec![]);
+        schedule.load_rounds(hrs, rounds, groups);
+        schedule
+    }
+    /// Replaces the groupings with a snapshot of those of the Round's, 'hrs',
+    /// reusing the schedule's buffers. The search loops use this to keep their
+    /// best schedule without allocating each time it improves.
+    pub fn load_rounds(&mut self,
+                       hrs    : &[HRound],
+                       rounds : &Rounds,
+                       groups : &Groups   ) {
+        self.rounds.resize_with(hrs.len(), Vec::new);
+        for (gvec, &hr) in self.rounds.iter_mut().zip(hrs) {
+            let hgs = rounds.groups(hr);
+            gvec.resize_with(hgs.len(), Vec::new);
+            for (members, &hg) in gvec.iter_mut().zip(hgs) {
+                members.clear();
+                members.extend(groups.member_set(hg).iter().map(|hp| hp.idx));
+                members.sort_unstable();
+            }
+        }
+    }
+    /// Returns the number of participants the schedule is for.
+    pub fn num_participants(&self) -> usize {
+        self.num_participants
+    }
+    /// Returns the number of participants each group can hold.
+    pub fn group_size(&self) -> usize {
+        self.group_size
+    }
+    /// Returns the number of rounds.
+    pub fn num_rounds(&self) -> usize {
+        self.rounds.len()
+    }
+    /// Returns the rounds, each a list of groups of participant indices.
+    pub fn rounds(&self) -> &[Vec<Vec<usize>>] {
+        &self.rounds
+    }
+    /// Indicates whether one participant sits out each round, as happens with
+    /// an odd number of participants in pairs.
+    pub fn has_bye(&self) -> bool {
+        self.group_size == 2 && self.num_participants % 2 == 1
+    }
+    /// Returns the participant who sits out round 'round_i', if the schedule
+    /// has a bye and exactly one participant is left out of the round.
+    pub fn bye(&self, round_i: usize) -> Option<usize> {
+        if !self.has_bye() {
+            return None;
+        }
+        let mut placed = vec![false; self.num_participants];
+        for &p in self.rounds[round_i].iter().flatten() {
+            if p < placed.len() {
+                placed[p] = true;
+            }
+        }
+        let mut left_out = (0..placed.len()).filter(|&p| !placed[p]);
+        match (left_out.next(), left_out.next()) {
+            (Some(p), None) => Some(p),
+            _               => None,
+        }
+    }
+    /// Returns the total number of placements over all rounds.
+    pub fn num_placements(&self) -> usize {
+        self.rounds.iter().flatten().map(|g| g.len()).sum()
+    }
+    /// Returns the canonical form of the schedule.
+    pub fn canonical(&self) -> CanonicalSchedule {
+        CanonicalSchedule::new(self)
+    }
+    /// Indicates whether the two schedules are the same apart from the order
+    /// of rounds, the order of groups, and the labeling of participants.
+    pub fn is_isomorphic(&self, other: &Schedule) -> bool {
+        self.num_participants == other.num_participants
+            && self.group_size == other.group_size
+            && self.num_rounds() == other.num_rounds()
+            && self.num_placements() == other.num_placements()
+            && self.canonical() == other.canonical()
+    }
+}
+impl fmt::Display for Schedule {
+    /// Writes the schedule in the same layout as `Rounds::to_string_multi()`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut group_id = 0;
+        for (round_i, round) in self.rounds.iter().enumerate() {
+            if round_i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "Round_{}:\n    ", round_i + 1)?;
+            for (group_i, group) in round.iter().enumerate() {
+                group_id += 1;
+                let p_strs = group.iter()
+                                  .map(|idx| format!("{:>2}", idx + 1))
+                                  .collect::<Vec<_>>();
+                if group_i > 0 {
+                    write!(f, "\n    ")?;
+                }
+                write!(f, "Group_{:<2}: [{}]", group_id, p_strs.join(", "))?;
+            }
+            if let Some(p) = self.bye(round_i) {
+                write!(f, "\n    Bye     : {:>2}", p + 1)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+impl FromStr for Schedule {
+    type Err = String;
+
+    /// Reads a schedule in the layout it's printed in. Each `Round_` line
+    /// starts a round, and each line with a bracketed list of participant 
+    /// ids is a group of that round. Other lines are ignored, so the output
+    /// of a run can be read back as is. The number of participants is taken
+    /// to be the highest id, and the group size is what the first round needs
+    /// to hold everyone, or 2 when the first round is pairs and a bye.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut rounds = vec![];
+        let mut max_id = 0;
+
+        for (line_i, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.starts_with("Round_") {
+                rounds.push(vec![]);
+                continue;
+            }
+            let (start, end) = match (line.find('['), line.rfind(']')) {
+                (Some(start), Some(end)) if start < end => (start, end),
+                _ => continue,
+            };
+            let round = rounds.last_mut().ok_or(format!(
+                            "Line {}: group found before any round.", 
+                            line_i + 1))?;
+            let mut group = vec![];
+            for id_str in line[start + 1..end].split(',') {
+                let id_str = id_str.trim();
+                if id_str.is_empty() { continue; }
+                let id = id_str.parse::<usize>()
+                               .ok()
+                               .filter(|&id| id > 0)
+                               .ok_or(format!("Line {}: invalid participant \
+                                               id ({}).", line_i + 1, id_str))?;
+                max_id = max_id.max(id);
+                group.push(id - 1);
+            }
+            round.push(group);
+        }
+        if rounds.is_empty() {
+            return Err("No rounds found.".to_string());
+        }
+        let n_groups   = rounds[0].len().max(1);
+        let group_size = Schedule::group_size_for(max_id, n_groups);
+
+        Ok(Schedule::new(max_id, group_size, rounds))
+    }
+}
+
+
@@ -0,0 +1,867 @@
+
+use crate::anneal::*;
+use crate::canon::*;
+use crate::checkpoint::*;
+use crate::exact::*;
+use crate::group::*;
+use crate::participant::*;
+use crate::participant_set::*;
+use crate::round::*;
+use crate::schedule::*;
+use crate::shuffle;
+use crate::tabu::*;
+
+use itertools::enumerate;
+use rand::prelude::*;
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+// Works like Python's random.randint().
+macro_rules! randint {
+    ( $start:expr, $end:expr ) => {
+        crate::rng::with_rng(|rng| rng.gen_range($start, $end + 1))
+    };
+}
+
+/// The parameters of a problem instance.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Problem {                 // Kirkman's Schoolgirl's   Conference
+    pub num_participants : usize,    //         15;                   70;
+    pub num_groups       : usize,    //          5;                   10;
+    pub num_rounds       : usize,    //          7;                    5;
+    pub num_attempts     : usize,
+}
+impl Problem {
+    /// Returns the number of participants in each group.
+    pub fn group_size(&self) -> usize {
+        self.num_participants / self.num_groups
+    }
+    /// Indicates whether one participant sits out each round, as happens with
+    /// an odd number of participants in pairs. Only the round robin
+    /// construction can schedule these.
+    pub fn has_bye(&self) -> bool {
+        self.num_groups > 1 
+            && self.num_participants == 2 * self.num_groups + 1
+    }
+    /// Returns the number of placements in a complete schedule.
+    pub fn num_placements(&self) -> usize {
+        self.num_groups * self.group_size() * self.num_rounds
+    }
+    /// Checks that the parameters describe a problem the solver can work on.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.num_participants == 0 || self.num_groups == 0
+                                      || self.num_rounds == 0 {
+            Err("The numbers of participants, groups and rounds must be \
+                 greater than 0.".to_string())
+        } else if self.num_participants > MAX_PARTICIPANTS {
+            Err(format!("The number of participants can't exceed {}.",
+                        MAX_PARTICIPANTS))
+        } else if !self.num_participants.is_multiple_of(self.num_groups)
+                  && !self.has_bye() {
+            Err(format!("{} participants can't be divided evenly into {} \
+                         groups.", self.num_participants, self.num_groups))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// The search algorithms that can be used to solve a problem.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Algorithm {
+    /// Build rounds up one participant at a time, regrouping participants of
+    /// earlier rounds to make openings, and restart when stuck. See `Solver`.
+    Regroup,
+    /// Swap participants between groups of complete rounds under simulated
+    /// annealing. See `Annealer`.
+    Anneal,
+    /// Swap participants between groups of complete rounds under tabu 
+    /// search. See `TabuSearch`.
+    Tabu,
+    /// Search every schedule, up to symmetry, until one is found or none is 
+    /// shown to exist. See `ExactSearch`.
+    Exact,
+}
+impl Algorithm {
+    /// Runs the algorithm on the problem, calling 'on_best' each time it 
+    /// finds a schedule with more placements than before. Problems with a bye
+    /// are left unsolved, as the searches expect groups that hold everyone.
+    pub fn solve_with<F>(self, problem: Problem, on_best: F) -> SolveResult
+    where
+        F: FnMut(&Schedule),
+    {
+        self.solve_until(problem, &CancelToken::new(), on_best)
+    }
+    /// Runs the algorithm like `solve_with()`, stopping early if 'cancel' is
+    /// cancelled.
+    pub fn solve_until<F>(self,
+                          problem : Problem,
+                          cancel  : &CancelToken,
+                          on_best : F          ) -> SolveResult
+    where
+        F: FnMut(&Schedule),
+    {
+        if problem.has_bye() {
+            let best = Schedule::new(problem.num_participants,
+                                     problem.group_size(), vec![]);
+            return SolveResult { solved: false, attempts: 0, best,
+                                 stats: None };
+        }
+        match self {
+            Algorithm::Regroup => {
+                let mut solver = Solver::new(problem);
+                solver.set_cancel(cancel.clone());
+                solver.solve_with(on_best)
+            },
+            Algorithm::Anneal => {
+                let mut annealer = Annealer::new(problem);
+                annealer.set_cancel(cancel.clone());
+                annealer.solve_with(on_best)
+            },
+            Algorithm::Tabu => {
+                let mut tabu = TabuSearch::new(problem);
+                tabu.set_cancel(cancel.clone());
+                tabu.solve_with(on_best)
+            },
+            Algorithm::Exact => {
+                let mut exact = ExactSearch::new(problem);
+                exact.set_cancel(cancel.clone());
+                let result = exact.solve_with(on_best);
+                SolveResult { solved   : result.solved,
+                              attempts : result.nodes as usize,
+                              best     : result.best,
+                              stats    : None }
+            },
+        }
+    }
+}
+impl FromStr for Algorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "regroup" => Ok(Algorithm::Regroup),
+            "anneal"  => Ok(Algorithm::Anneal),
+            "tabu"    => Ok(Algorithm::Tabu),
+            "exact"   => Ok(Algorithm::Exact),
+            _         => Err(format!("Unknown algorithm {}.", s)),
+        }
+    }
+}
+impl fmt::Display for Algorithm {
+    /// Writes the name `from_str()` reads.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Algorithm::Regroup => write!(f, "regroup"),
+            Algorithm::Anneal  => write!(f, "anneal"),
+            Algorithm::Tabu    => write!(f, "tabu"),
+            Algorithm::Exact   => write!(f, "exact"),
+        }
+    }
+}
+
+/// The order in which the regroup solver places the participants of a round
+/// after the first.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Placement {
+    /// Shuffle the participants each round and place them in that order.
+    Random,
+    /// Place next the participant with the fewest groups they can still join,
+    /// breaking ties at random. Participants no group is open to are then
+    /// found early, before the round fills up around them.
+    MostConstrained,
+}
+impl FromStr for Placement {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "random"      => Ok(Placement::Random),
+            "constrained" => Ok(Placement::MostConstrained),
+            _             => Err(format!("Unknown placement order {}.", s)),
+        }
+    }
+}
+impl fmt::Display for Placement {
+    /// Writes the name `from_str()` reads.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Placement::Random          => write!(f, "random"),
+            Placement::MostConstrained => write!(f, "constrained"),
+        }
+    }
+}
+
+/// How the regroup solver picks the regroups that might make an opening for a
+/// participant who can't join any group.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RegroupChoice {
+    /// Try other participants in turn, each in a random round.
+    Random,
+    /// First try to undo the meetings that keep the participant out of the
+    /// open groups, by regrouping the participant or the member they met in
+    /// the round they met in. Fall back to random choices if none works.
+    Conflict,
+}
+impl FromStr for RegroupChoice {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "random"   => Ok(RegroupChoice::Random),
+            "conflict" => Ok(RegroupChoice::Conflict),
+            _          => Err(format!("Unknown regroup choice {}.", s)),
+        }
+    }
+}
+impl fmt::Display for RegroupChoice {
+    /// Writes the name `from_str()` reads.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RegroupChoice::Random   => write!(f, "random"),
+            RegroupChoice::Conflict => write!(f, "conflict"),
+        }
+    }
+}
+
+/// What the regroup solver does when it gets stuck in a round.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Restart {
+    /// Start over from the first round.
+    Full,
+    /// Undo the round it got stuck in and some of the rounds before it,
+    /// keeping the rest. How many rounds to undo follows the Luby sequence,
+    /// 1, 1, 2, 1, 1, 2, 4, 1, ..., so mostly only the last round or two are
+    /// redone, and now and then many more.
+    Partial,
+}
+impl FromStr for Restart {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "full"    => Ok(Restart::Full),
+            "partial" => Ok(Restart::Partial),
+            _         => Err(format!("Unknown restart strategy {}.", s)),
+        }
+    }
+}
+impl fmt::Display for Restart {
+    /// Writes the name `from_str()` reads.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Restart::Full    => write!(f, "full"),
+            Restart::Partial => write!(f, "partial"),
+        }
+    }
+}
+
+/// The outcome of a call to `Solver::solve()`.
+#[derive(Clone, Debug)]
+pub struct SolveResult {
+    /// Whether every participant was grouped in every round.
+    pub solved   : bool,
+    /// The number of attempts the search made, including those before the
+    /// checkpoint of one resumed by `Solver::from_checkpoint()`.
+    pub attempts : usize,
+    /// The solution, or the schedule with the most placements if unsolved.
+    pub best     : Schedule,
+    /// The counts of the regroup solver's work, including that of earlier
+    /// searches by the same Solver. None for the other algorithms.
+    pub stats    : Option<SolverStats>,
+}
+
+/// A flag for stopping a search from elsewhere, such as another thread or a
+/// Ctrl-C handler. Clones share the flag. The searches check it as they go,
+/// and once it's set they stop and return their best schedule so far, as
+/// they do when they run out of attempts.
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken {
+    flag : Arc<AtomicBool>,
+}
+impl CancelToken {
+    /// Creates a CancelToken that isn't cancelled.
+    pub fn new() -> Self {
+        CancelToken::default()
+    }
+    /// Asks the searches checking the token to stop.
+    pub fn cancel(&self) {
+        self.flag.store(true, Ordering::Relaxed);
+    }
+    /// Indicates whether `cancel()` has been called.
+    pub fn is_cancelled(&self) -> bool {
+        self.flag.load(Ordering::Relaxed)
+    }
+}
+
+/// Counts of the work done by a Solver, for seeing where a search spends its
+/// time and comparing strategies.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SolverStats {
+    /// The number of attempts made.
+    pub attempts          : usize,
+    /// The number of attempts that got stuck and ended in a restart.
+    pub restarts          : usize,
+    /// The number of restarts from each round, indexed by the round that got
+    /// stuck.
+    pub restarts_by_round : Vec<usize>,
+    /// The number of those restarts that kept earlier rounds.
+    pub partial           : usize,
+    /// The deepest round reached, counting from 1.
+    pub deepest           : usize,
+    /// The number of times `try_join_groups()` found no group open to a
+    /// participant.
+    pub blocked           : usize,
+    /// The number of calls to `try_regroup()`.
+    pub regroups          : usize,
+    /// The number of regroups that moved the participant to a free seat.
+    pub moved             : usize,
+    /// The number of regroups that swapped the participant with another.
+    pub swapped           : usize,
+    /// The number of regroups that found no move.
+    pub failed            : usize,
+}
+
+/// What a Solver calls with each checkpoint it takes.
+type CheckpointHook = Box<dyn FnMut(&Checkpoint)>;
+
+/// The randomized solver.
+/// Participants are grouped round by round. When one can't find a group
+/// without meeting an acquaintance, participants are regrouped in earlier
+/// rounds to make an opening. If no regrouping helps, the attempt is abandoned
+/// and a fresh one is started.
+pub struct Solver {
+    problem     : Problem,
+    parts       : Participants,
+    groups      : Groups,
+    rounds      : Rounds,
+    hpart_vec_a : Vec<HParticipant>,
+    hpart_vec_b : Vec<HParticipant>,
+    hgroup_vec  : Vec<HGroup>,
+    hround_vec  : Vec<HRound>,
+    hpart_b_idx : usize,
+    attempts    : usize,
+    start       : Vec<Vec<Vec<usize>>>,
+    placement   : Placement,
+    restart     : Restart,
+    choice      : RegroupChoice,
+    conflicts   : Vec<(HParticipant, usize)>,
+    kept        : usize,
+    n_partial   : usize,
+    stats       : SolverStats,
+    resumed     : Vec<Vec<Vec<usize>>>,
+    prior_best  : Option<Schedule>,
+    checkpoints : Option<CheckpointHook>,
+    every       : Duration,
+    last_taken  : Instant,
+    cancel      : CancelToken,
+}
+impl Solver {
+    /// Creates a Solver for the problem. The problem is expected to have been
+    /// validated.
+    pub fn new(problem: Problem) -> Self {
+        let num_groups_total = problem.num_groups * problem.num_rounds;
+        let group_size       = problem.group_size() as u32;
+
+        // The aggregate objects that handles belong to in the program.
+        let mut parts  = Participants::new();
+        let mut groups = Groups::new();
+        let mut rounds = Rounds::new();
+
+        // Allocate the objects of the program and get their handles.
+        let hpart_vec_a = parts.hcalloc(problem.num_participants);
+        let hpart_vec_b = hpart_vec_a.clone();
+        let hgroup_vec  = groups.hcalloc(num_groups_total, group_size);
+        let hround_vec  = rounds.hcalloc(problem.num_rounds);
+        let n_rounds    = problem.num_rounds;
+
+        Solver { problem, parts, groups, rounds,
+                 hpart_vec_a, hpart_vec_b, hgroup_vec, hround_vec,
+                 hpart_b_idx: 0, attempts: 0, start: vec![],
+                 placement: Placement::Random, restart: Restart::Full,
+                 choice: RegroupChoice::Random, conflicts: vec![],
+                 kept: 0, n_partial: 0,
+                 stats: SolverStats { restarts_by_round: vec![0; n_rounds],
+                                      ..SolverStats::default() },
+                 resumed: vec![], prior_best: None, checkpoints: None,
+                 every: Duration::ZERO, last_taken: Instant::now(),
+                 cancel: CancelToken::new() }
+    }
+    /// Creates a Solver that keeps the rounds of 'start', such as those of a
+    /// construction, as they are and only searches for the rounds after them.
+    /// The starting rounds are expected to be valid for the problem, with no
+    /// more rounds than it has.
+    pub fn with_start(problem: Problem, start: &Schedule) -> Self {
+        let mut solver = Solver::new(problem);
+        solver.start   = start.rounds().to_vec();
+        solver
+    }
+    /// Creates a Solver that carries on the search a checkpoint was taken of.
+    /// The generator is reseeded with the checkpoint's seed, so with the same
+    /// checkpoints taken the search goes on as it would have without the
+    /// break. The first search's result counts the attempts made before the
+    /// checkpoint along with its own, but the counts of `stats()` start over.
+    pub fn from_checkpoint(cp: &Checkpoint) -> Self {
+        let mut solver = Solver::with_start(cp.problem, &cp.start);
+        solver.placement  = cp.placement;
+        solver.restart    = cp.restart;
+        solver.choice     = cp.choice;
+        solver.attempts   = cp.attempts;
+        solver.n_partial  = cp.luby;
+        solver.resumed    = cp.kept.rounds().to_vec();
+        solver.prior_best = Some(cp.best.clone());
+        crate::rng::seed(cp.seed);
+        solver
+    }
+    /// Has 'f' called with a checkpoint of the search after the first attempt
+    /// that ends 'every' or more after the last checkpoint was taken. Taking a
+    /// checkpoint reseeds the generator with a seed drawn from it, which the
+    /// checkpoint keeps.
+    pub fn set_checkpoints<F>(&mut self, every: Duration, f: F)
+    where
+        F: FnMut(&Checkpoint) + 'static,
+    {
+        self.every       = every;
+        self.last_taken  = Instant::now();
+        self.checkpoints = Some(Box::new(f));
+    }
+    /// Returns the problem being solved.
+    pub fn problem(&self) -> &Problem {
+        &self.problem
+    }
+    /// Sets the order in which participants are placed. The default is
+    /// `Placement::Random`.
+    pub fn set_placement(&mut self, placement: Placement) {
+        self.placement = placement;
+    }
+    /// Returns the order in which participants are placed.
+    pub fn placement(&self) -> Placement {
+        self.placement
+    }
+    /// Sets what to do when the search gets stuck. The default is
+    /// `Restart::Full`.
+    pub fn set_restart(&mut self, restart: Restart) {
+        self.restart = restart;
+    }
+    /// Returns what is done when the search gets stuck.
+    pub fn restart(&self) -> Restart {
+        self.restart
+    }
+    /// Sets how regroups are picked when a participant can't join a group.
+    /// The default is `RegroupChoice::Random`.
+    pub fn set_regroup_choice(&mut self, choice: RegroupChoice) {
+        self.choice = choice;
+    }
+    /// Returns how regroups are picked.
+    pub fn regroup_choice(&self) -> RegroupChoice {
+        self.choice
+    }
+    /// Makes the searches stop between attempts once 'cancel' is cancelled.
+    pub fn set_cancel(&mut self, cancel: CancelToken) {
+        self.cancel = cancel;
+    }
+    /// Returns the total number of attempts made so far.
+    pub fn attempts(&self) -> usize {
+        self.attempts
+    }
+    /// Returns the counts of the work done so far.
+    pub fn stats(&self) -> SolverStats {
+        SolverStats { attempts: self.attempts, ..self.stats.clone() }
+    }
+    /// Searches until the problem is solved or the attempts run out.
+    pub fn solve(&mut self) -> SolveResult {
+        self.solve_with(|_| {})
+    }
+    /// Searches like `solve()`, calling 'on_best' each time an attempt makes
+    /// more placements than any before it in this search.
+    pub fn solve_with<F>(&mut self, mut on_best: F) -> SolveResult
+    where
+        F: FnMut(&Schedule),
+    {
+        let num_placements   = self.problem.num_placements() as u32;
+        let mut start        = self.attempts;
+        let mut best_grouped = 0;
+        let mut best         = self.schedule();
+
+        if let Some(prior_best) = self.prior_best.take() {
+            // The search resumed counts the attempts before its checkpoint.
+            start        = 0;
+            best_grouped = prior_best.num_placements() as u32;
+            best         = prior_best;
+        }
+        while self.attempts < self.problem.num_attempts {
+            if self.cancel.is_cancelled() {
+                // Leave a checkpoint of where the search stopped.
+                if self.checkpoints.is_some() {
+                    self.take_checkpoint(&best);
+                }
+                break;
+            }
+            self.attempts += 1;
+            self.attempt();
+            self.resumed.clear();
+
+            // Determine if we have the best distribution so far.
+            let num_grouped = self.rounds.num_grouped(&self.hround_vec,
+                                                      &self.groups);
+            if num_grouped > best_grouped {
+                best_grouped = num_grouped;
+                best.load_rounds(&self.hround_vec, &self.rounds, &self.groups);
+                on_best(&best);
+            }
+            if num_grouped >= num_placements {
+                // If all groups are full, the problem is solved.
+                return SolveResult { solved   : true,
+                                     attempts : self.attempts - start,
+                                     best,
+                                     stats    : Some(self.stats()) };
+            }
+            if self.checkpoints.is_some()
+                && self.last_taken.elapsed() >= self.every {
+
+                self.take_checkpoint(&best);
+            }
+        }
+        SolveResult { solved   : false,
+                      attempts : self.attempts - start,
+                      best,
+                      stats    : Some(self.stats()) }
+    }
+    /// Returns an iterator that keeps searching after each success and yields
+    /// solutions that aren't isomorphic to any yielded before. The iterator
+    /// ends when the attempts run out.
+    pub fn solutions(self) -> Solutions {
+        Solutions { solver: self, seen: HashSet::new() }
+    }
+    /// Calls the checkpoint hook with the state of the search after the
+    /// attempt just made, which found 'best' the best schedule so far.
+    fn take_checkpoint(&mut self, best: &Schedule) {
+        let seed = crate::rng::with_rng(|rng| rng.gen());
+        crate::rng::seed(seed);
+
+        let n_parts    = self.problem.num_participants;
+        let group_size = self.problem.group_size();
+        let kept       = Schedule::from_rounds(&self.hround_vec[..self.kept],
+                                               &self.rounds,
+                                               &self.groups,
+                                               n_parts,
+                                               group_size);
+        let cp = Checkpoint {
+            problem   : self.problem,
+            placement : self.placement,
+            restart   : self.restart,
+            choice    : self.choice,
+            attempts  : self.attempts,
+            seed,
+            luby      : self.n_partial,
+            start     : Schedule::new(n_parts, group_size, self.start.clone()),
+            kept,
+            best      : best.clone(),
+        };
+        if let Some(f) = self.checkpoints.as_mut() {
+            f(&cp);
+        }
+        self.last_taken = Instant::now();
+    }
+    /// Returns a snapshot of the current groupings.
+    fn schedule(&self) -> Schedule {
+        Schedule::from_rounds(&self.hround_vec,
+                              &self.rounds,
+                              &self.groups,
+                              self.problem.num_participants,
+                              self.problem.group_size())
+    }
+    /// Makes one attempt at grouping the participants for every round,
+    /// starting fresh, or, after a partial restart, from the rounds kept.
+    fn attempt(&mut self) {
+        let first_free  = self.start.len().max(1);
+        let mut round_i = self.kept;
+
+        if round_i == 0 || !self.resumed.is_empty() {
+            // A resumed search rebuilds the rounds it kept from scratch.
+            round_i = 0;
+            // Reset all objects for another attempt at solving the problem.
+            self.parts .reset();
+            self.groups.reset();
+            self.rounds.reset();
+            self.parts.sort_slice(&mut self.hpart_vec_a);
+        } else {
+            // Undo the rounds after those kept, last first.
+            self.stats.partial += 1;
+            for undo_i in (round_i..self.hround_vec.len()).rev() {
+                self.undo_round(undo_i);
+            }
+        }
+        self.kept = 0;
+
+        while round_i < self.hround_vec.len() {
+            self.stats.deepest = self.stats.deepest.max(round_i + 1);
+
+            if !self.group_round(round_i) {
+                self.stats.restarts += 1;
+                self.stats.restarts_by_round[round_i] += 1;
+
+                if self.restart == Restart::Partial && round_i >= first_free {
+                    // Go back as many rounds as the restart schedule says,
+                    // counting the one that failed.
+                    self.n_partial += 1;
+                    let back  = luby(self.n_partial);
+                    self.kept = (round_i + 1).saturating_sub(back)
+                                             .max(first_free);
+                }
+                return;
+            }
+            round_i += 1;
+        }
+    }
+    /// Groups the participants for the 'round_i'th round. Returns `false` if
+    /// some participant couldn't be grouped.
+    fn group_round(&mut self, round_i: usize) -> bool {
+        let n_groups        = self.problem.num_groups;
+        let num_regroups    = self.problem.num_participants * 2;
+        let hpart_vec_b_len = self.hpart_vec_b.len();
+        let first_free      = self.start.len().max(1);
+        let hround          = self.hround_vec[round_i];
+
+        // Slice the groups per round.
+        let gr_start     = n_groups * round_i;
+        let gr_end       = gr_start + n_groups;
+        let hgroup_slice = &self.hgroup_vec[gr_start..gr_end];
+
+        // Add the groups slice to the current round.
+        self.rounds.add_groups(hround, hgroup_slice);
+
+        // Prepare the participants to be grouped again.
+        self.parts.prepare_for_new_round();
+
+        if round_i < self.resumed.len().max(self.start.len()) {
+            // Starting rounds, and the rounds a resumed search kept, are
+            // grouped as given.
+            let start = match self.resumed.get(round_i) {
+                Some(kept) => kept,
+                None       => &self.start[round_i],
+            };
+            for (&hgroup, members) in hgroup_slice.iter().zip(start) {
+                for &idx in members {
+                    if !self.parts.try_join(HParticipant { idx }, hgroup,
+                                            &mut self.rounds,
+                                            &mut self.groups) {
+                        return false;
+                    }
+                }
+            }
+            return true;
+        }
+
+        if round_i > 0 {
+            // Randomize the order in which participants are grouped after
+            // the first round. They're sorted first so the order only
+            // depends on the generator, which a resumed search relies on.
+            self.parts.sort_slice(&mut self.hpart_vec_a);
+            self.parts.sort_slice(&mut self.hpart_vec_b);
+            shuffle!(self.hpart_vec_a);
+            shuffle!(self.hpart_vec_b);
+            self.hpart_b_idx = 0;
+        }
+
+        'grouping_participants: for part_i in 0..self.hpart_vec_a.len() {
+
+            if round_i > 0 && self.placement == Placement::MostConstrained {
+                self.move_most_constrained(part_i, hround);
+            }
+            let hpart_a = self.hpart_vec_a[part_i];
+
+            'trying_regroups: for _ in 0..num_regroups {
+
+                // Try to find a group for hpart_a.
+                if self.parts.try_join_groups(hpart_a,
+                                              hround,
+                                              &mut self.rounds,
+                                              &mut self.groups) {
+
+                    // Participant found group, move to next participant.
+                    continue 'grouping_participants;
+                } else if round_i == 0 {
+                    // There are no earlier rounds to regroup in.
+                    return false;
+                }
+                // Didn't find a group - get another participant to
+                // regroup to see if an opening can be made.
+                self.stats.blocked += 1;
+
+                if self.choice == RegroupChoice::Conflict
+                    && self.regroup_conflict(hpart_a, round_i) {
+
+                    continue 'trying_regroups;
+                }
+                for _ in 0..hpart_vec_b_len {
+                    self.hpart_b_idx += 1;
+                    self.hpart_b_idx %= hpart_vec_b_len;
+                    let hpart_b = self.hpart_vec_b[self.hpart_b_idx];
+
+                    if !self.parts.is_grouped(hpart_b) { continue; }
+
+                    // Pick a round to make the move in, leaving the
+                    // starting rounds alone.
+                    let round_num = randint!(first_free, round_i);
+
+                    // Attempt the regroup. On success go back and try
+                    // again to group hpart_a.
+                    let hr     = self.hround_vec[round_num];
+                    let result = self.parts.try_regroup(hpart_b,
+                                                        hr,
+                                                        &mut self.rounds,
+                                                        &mut self.groups);
+                    if self.count_regroup(result) {
+                        continue 'trying_regroups;
+                    }
+                }
+                // The regroup loop completed, which means all the other
+                // participants tried to regroup and none succeeded.
+                return false;
+            }
+        }
+        true
+    }
+    /// Tries to make an opening for 'hp' in the 'round_i'th round by undoing
+    /// one of the meetings that keep it out of the round's open groups. For
+    /// each member of an open group 'hp' has met, 'hp' and the member are
+    /// each tried, in random order, at regrouping in the round they met in.
+    /// Returns `true` if a regroup was made.
+    fn regroup_conflict(&mut self, hp: HParticipant, round_i: usize) -> bool {
+        let first_free = self.start.len().max(1);
+        let hround     = self.hround_vec[round_i];
+        self.conflicts.clear();
+
+        for &hg in self.rounds.groups(hround) {
+            if self.groups.full(hg) { continue; }
+
+            for hop in self.groups.member_set(hg).iter() {
+                if !self.parts.is_acquainted_participant(hp, hop) { continue; }
+
+                // Find the round they met in.
+                for r in first_free..round_i {
+                    let hr  = self.hround_vec[r];
+                    let hmg = self.rounds.participant_group(hr, hp);
+                    if hmg != HGROUP_NULL && self.groups.has(hmg, hop) {
+                        self.conflicts.push((hp,  r));
+                        self.conflicts.push((hop, r));
+                        break;
+                    }
+                }
+            }
+        }
+        shuffle!(self.conflicts);
+
+        for i in 0..self.conflicts.len() {
+            let (hmover, r) = self.conflicts[i];
+            let result = self.parts.try_regroup(hmover,
+                                                self.hround_vec[r],
+                                                &mut self.rounds,
+                                                &mut self.groups);
+            if self.count_regroup(result) {
+                return true;
+            }
+        }
+        false
+    }
+    /// Counts the outcome of a call to `try_regroup()`. Returns `true` if it
+    /// made a move.
+    fn count_regroup(&mut self, result: Result<HParticipant, ()>) -> bool {
+        self.stats.regroups += 1;
+        match result {
+            Ok(hp) if hp == HPARTICIPANT_NULL => self.stats.moved   += 1,
+            Ok(_)                             => self.stats.swapped += 1,
+            Err(())                           => self.stats.failed  += 1,
+        }
+        result.is_ok()
+    }
+    /// Takes every participant out of the groups of the 'round_i'th round,
+    /// along with the acquaintances they made there, and takes the groups out
+    /// of the round.
+    fn undo_round(&mut self, round_i: usize) {
+        let hround = self.hround_vec[round_i];
+        for i in 0..self.rounds.groups(hround).len() {
+            let hg      = self.rounds.groups(hround)[i];
+            let members = *self.groups.member_set(hg);
+            for hp in members.iter() {
+                self.parts.leave_group(hp, hg, &mut self.rounds,
+                                       &mut self.groups);
+            }
+        }
+        self.rounds.clear(hround);
+    }
+    /// Swaps the participant with the fewest groups of round 'hr' open to
+    /// them, among those from 'from' on in the placement order, into position
+    /// 'from'. The first one found wins a tie, so ties go by the shuffled
+    /// order.
+    fn move_most_constrained(&mut self, from: usize, hr: HRound) {
+        let gv         = self.rounds.groups(hr);
+        let mut most   = from;
+        let mut fewest = usize::MAX;
+
+        for (i, &hp) in enumerate(&self.hpart_vec_a[from..]) {
+            let n_open = gv.iter()
+                           .filter(|&&hg| !self.groups.full(hg) &&
+                                          !self.parts.is_acquainted(
+                                              hp, hg, &self.groups))
+                           .count();
+            if n_open < fewest {
+                fewest = n_open;
+                most   = from + i;
+                if n_open == 0 { break; }
+            }
+        }
+        self.hpart_vec_a.swap(from, most);
+    }
+}
+
+/// Returns the 'i'th term, counting from 1, of the Luby sequence 1, 1, 2, 1,
+/// 1, 2, 4, 1, 1, 2, 1, 1, 2, 4, 8, ...
+fn luby(i: usize) -> usize {
+    let mut k = 1;
+    while (1 << k) - 1 < i {
+        k += 1;
+    }
+    if (1 << k) - 1 == i {
+        1 << (k - 1)
+    } else {
+        luby(i - (1 << (k - 1)) + 1)
+    }
+}
+
+/// An iterator over distinct solutions.
+/// This iterator is returned by `Solver::solutions()`. Solutions that only
+/// differ by the order of rounds, the order of groups, or by a relabeling of
+/// the participants are only yielded once.
+pub struct Solutions {
+    solver : Solver,
+    seen   : HashSet<CanonicalSchedule>,
+}
+impl Solutions {
+    /// Returns the solver doing the search.
+    pub fn solver(&self) -> &Solver {
+        &self.solver
+    }
+}
+impl Iterator for Solutions {
+    type Item = Schedule;
+
+    fn next(&mut self) -> Option<Schedule> {
+        loop {
+            let result = self.solver.solve();
+            if !result.solved {
+                return None;
+            }
+            if self.seen.insert(result.best.canonical()) {
+                return Some(result.best);
+            }
+        }
+    }
+}
+
+
@@ -0,0 +1,179 @@
+
+use crate::assignment::*;
+use crate::rng;
+use crate::schedule::*;
+use crate::solver::*;
+
+use rand::prelude::*;
+
+/// The fewest iterations a moved participant stays tabu in its round.
+const TENURE_MIN : usize = 2;
+/// The most iterations a moved participant stays tabu in its round.
+const TENURE_MAX : usize = 6;
+/// The iterations allowed without improving on the best cost of an attempt
+/// before the attempt is given up.
+const STALL      : usize = 10_000;
+
+/// The tabu search solver.
+/// Like `Annealer`, this works on a complete assignment of participants to
+/// groups. At each iteration every swap of a participant with a conflict in
+/// some round, that is one who meets someone they meet in another round, with a
+/// participant of another group of that round is scored, and the best one is
+/// made even if it makes things worse. The two participants moved may not be
+/// moved again in that round for a random number of iterations, the tenure,
+/// which keeps the search from cycling. A tabu move is still allowed if it
+/// reaches a lower cost than any before it in the attempt, the aspiration
+/// criterion. An attempt is given up, and a fresh one started, when it stops
+/// improving.
+pub struct TabuSearch {
+    assign   : Assignment,
+    tabu     : Vec<Vec<usize>>,
+    attempts : usize,
+    cancel   : CancelToken,
+}
+impl TabuSearch {
+    /// Creates a TabuSearch for the problem. The problem is expected to have
+    /// been validated.
+    pub fn new(problem: Problem) -> Self {
+        let tabu = vec![vec![0; problem.num_participants]; problem.num_rounds];
+        TabuSearch { assign: Assignment::new(problem), tabu, attempts: 0,
+                     cancel: CancelToken::new() }
+    }
+    /// Makes the searches stop once 'cancel' is cancelled.
+    pub fn set_cancel(&mut self, cancel: CancelToken) {
+        self.cancel = cancel;
+    }
+    /// Returns the total number of attempts made so far.
+    pub fn attempts(&self) -> usize {
+        self.attempts
+    }
+    /// Searches until the problem is solved or the attempts run out.
+    pub fn solve(&mut self) -> SolveResult {
+        self.solve_with(|_| {})
+    }
+    /// Searches like `solve()`, calling 'on_best' each time the search finds
+    /// a schedule with more placements than any before it. As with the
+    /// annealer, the participants behind repeated meetings are left out of
+    /// the schedules passed to 'on_best'.
+    /// Problems with a bye, which only the round robin construction can
+    /// schedule, are returned unsolved at once.
+    pub fn solve_with<F>(&mut self, mut on_best: F) -> SolveResult
+    where
+        F: FnMut(&Schedule),
+    {
+        let     start = self.attempts;
+        let mut best  = self.assign.valid_schedule();
+        let mut rng   = rng::fork();
+
+        if self.assign.problem.has_bye() {
+            return SolveResult { solved   : false,
+                                 attempts : 0,
+                                 best,
+                                 stats    : None };
+        }
+
+        while self.attempts < self.assign.problem.num_attempts
+            && !self.cancel.is_cancelled() {
+
+            self.attempts += 1;
+            self.assign.randomize(&mut rng);
+            if self.assign.improve(&mut best) {
+                on_best(&best);
+            }
+            for round in &mut self.tabu {
+                round.iter_mut().for_each(|t| *t = 0);
+            }
+            let mut cost      = self.assign.cost();
+            if cost > 0 && !self.assign.has_moves() {
+                // Every deal is the same, and there's no swap to make.
+                break;
+            }
+            let mut best_cost = cost;
+            let mut iter      = 0;
+            let mut stall     = 0;
+
+            while cost > 0 && stall < STALL && !self.cancel.is_cancelled() {
+                iter += 1;
+                let (r, p, q, delta) = match self.best_move(iter, cost,
+                                                            best_cost,
+                                                            &mut rng) {
+                    Some(m) => m,
+                    None    => break,
+                };
+                self.assign.swap(r, p, q);
+                cost = (cost as i32 + delta) as u32;
+
+                let tenure = rng.gen_range(TENURE_MIN, TENURE_MAX + 1);
+                self.tabu[r][p] = iter + tenure;
+                self.tabu[r][q] = iter + tenure;
+
+                if cost < best_cost {
+                    best_cost = cost;
+                    stall     = 0;
+                    if self.assign.improve(&mut best) {
+                        on_best(&best);
+                    }
+                } else {
+                    stall += 1;
+                }
+            }
+            if cost == 0 {
+                return SolveResult { solved   : true,
+                                     attempts : self.attempts - start,
+                                     best,
+                                     stats    : None };
+            }
+        }
+        SolveResult { solved   : false,
+                      attempts : self.attempts - start,
+                      best,
+                      stats    : None }
+    }
+    /// Scores the conflict-directed swaps and returns the best allowed one as
+    /// (round, participant, participant, change in cost). Ties are broken at
+    /// random. None is returned if every move is tabu.
+    fn best_move<R: Rng>(&self,
+                         iter      : usize,
+                         cost      : u32,
+                         best_cost : u32,
+                         rng       : &mut R     )
+        -> Option<(usize, usize, usize, i32)>
+    {
+        let n_parts    = self.assign.problem.num_participants;
+        let n_rounds   = self.assign.problem.num_rounds;
+        let mut found  = None;
+        let mut best_d = i32::MAX;
+        let mut n_ties = 0;
+
+        for r in 0..n_rounds {
+            for p in 0..n_parts {
+                if !self.assign.is_conflicted(r, p) { continue; }
+
+                for q in 0..n_parts {
+                    if self.assign.group_of[r][q] == self.assign.group_of[r][p] {
+                        continue;
+                    }
+                    let delta = self.assign.swap_delta(r, p, q);
+                    let tabu  = self.tabu[r][p] > iter || self.tabu[r][q] > iter;
+
+                    if tabu && (cost as i32 + delta) as u32 >= best_cost {
+                        continue;
+                    }
+                    if delta < best_d {
+                        best_d = delta;
+                        found  = Some((r, p, q, delta));
+                        n_ties = 1;
+                    } else if delta == best_d {
+                        n_ties += 1;
+                        if rng.gen_range(0, n_ties) == 0 {
+                            found = Some((r, p, q, delta));
+                        }
+                    }
+                }
+            }
+        }
+        found
+    }
+}
+
+
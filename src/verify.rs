@@ -0,0 +1,133 @@
+
+use std::collections::BTreeMap;
+use std::fmt;
+use crate::schedule::*;
+
+/// A rule of the problem that a schedule breaks.
+/// Rounds are numbered from 1, by their position in the schedule. Groups are
+/// given by their id in the printed schedule, `Group_1` for the first group
+/// of the first round, and carrying on through the rounds after it.
+/// Participants are given by index, as in Schedule, and printed as ids.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Violation {
+    /// The two participants were grouped together in more than one round.
+    RepeatedPair     { first: usize, second: usize, rounds: Vec<usize> },
+    /// The participant isn't in any group of the round.
+    Missing          { round: usize, participant: usize },
+    /// The participant is placed more than once in the round.
+    PlacedTwice      { round: usize, participant: usize, groups: Vec<usize> },
+    /// The participant is outside the range of the schedule's participants.
+    Unknown          { round: usize, group: usize, participant: usize },
+    /// The group holds more members than a group can hold.
+    OverCapacity     { round: usize, group: usize, size: usize,
+                       capacity: usize },
+    /// The group holds fewer members than a full group.
+    UnderCapacity    { round: usize, group: usize, size: usize,
+                       capacity: usize },
+}
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use Violation::*;
+        match self {
+            RepeatedPair { first, second, rounds } => {
+                let r_strs = rounds.iter().map(|r| r.to_string())
+                                          .collect::<Vec<_>>();
+                write!(f, "Participants {} and {} are grouped together in \
+                           rounds {}.", first + 1, second + 1,
+                                        r_strs.join(", "))
+            },
+            Missing { round, participant } => {
+                write!(f, "Round_{}: participant {} is missing.",
+                       round, participant + 1)
+            },
+            PlacedTwice { round, participant, groups } => {
+                let g_strs = groups.iter().map(|g| g.to_string())
+                                          .collect::<Vec<_>>();
+                write!(f, "Round_{}: participant {} is placed {} times, in \
+                           Group_{}.", round, participant + 1, groups.len(),
+                                       g_strs.join(", Group_"))
+            },
+            Unknown { round, group, participant } => {
+                write!(f, "Round_{}: Group_{} has unknown participant {}.",
+                       round, group, participant + 1)
+            },
+            OverCapacity { round, group, size, capacity } => {
+                write!(f, "Round_{}: Group_{} has {} members, more than its \
+                           capacity of {}.", round, group, size, capacity)
+            },
+            UnderCapacity { round, group, size, capacity } => {
+                write!(f, "Round_{}: Group_{} has {} members, fewer than its \
+                           capacity of {}.", round, group, size, capacity)
+            },
+        }
+    }
+}
+
+/// Checks the schedule against the rules of the problem and returns every
+/// violation found. An empty vector means the schedule is valid. With an odd
+/// number of participants in pairs, one participant may sit out each round.
+/// The violations of each round come first, in round order, followed by the
+/// repeated pairs.
+pub fn verify(schedule: &Schedule) -> Vec<Violation> {
+    use Violation::*;
+    let n_parts      = schedule.num_participants();
+    let capacity     = schedule.group_size();
+    let mut found    = vec![];
+    let mut met      = BTreeMap::<(usize, usize), Vec<usize>>::new();
+    let mut group_no = 0;
+
+    for (round_i, round) in schedule.rounds().iter().enumerate() {
+        let round_no   = round_i + 1;
+        let mut placed = vec![vec![]; n_parts];
+
+        for group in round {
+            group_no += 1;
+
+            if group.len() > capacity {
+                found.push(OverCapacity { round: round_no, group: group_no,
+                                          size: group.len(), capacity });
+            } else if group.len() < capacity {
+                found.push(UnderCapacity { round: round_no, group: group_no,
+                                           size: group.len(), capacity });
+            }
+            for &p in group {
+                if p >= n_parts {
+                    found.push(Unknown { round: round_no, group: group_no,
+                                         participant: p });
+                } else {
+                    placed[p].push(group_no);
+                }
+            }
+            // A participant listed twice in a group is only reported once, as
+            // placed twice, rather than as meeting the others twice.
+            let mut members = group.iter().filter(|&&p| p < n_parts)
+                                          .collect::<Vec<_>>();
+            members.sort_unstable();
+            members.dedup();
+
+            for (i, &&p) in members.iter().enumerate() {
+                for &&q in &members[i + 1..] {
+                    met.entry((p, q)).or_default().push(round_no);
+                }
+            }
+        }
+        // The participant sitting out a round of pairs isn't missing.
+        let bye = schedule.bye(round_i);
+        for (p, groups) in placed.into_iter().enumerate() {
+            if groups.is_empty() && bye != Some(p) {
+                found.push(Missing { round: round_no, participant: p });
+            } else if groups.len() > 1 {
+                found.push(PlacedTwice { round: round_no, participant: p,
+                                         groups });
+            }
+        }
+    }
+    for ((first, second), rounds) in met {
+        if rounds.len() > 1 {
+            found.push(RepeatedPair { first, second, rounds });
+        }
+    }
+    found
+}
+
+
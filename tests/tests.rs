@@ -1,10 +1,35 @@
+#![allow(clippy::bool_comparison, clippy::assertions_on_constants)]
+
 
 // todd:socialx$ cargo test --test tests
 
+use socialx::anneal::*;
+use socialx::bench::{self, *};
+use socialx::canon::*;
+use socialx::checkpoint::*;
+use socialx::construct::*;
+use socialx::exact::*;
+use socialx::meetings::*;
+use socialx::mols::*;
 use socialx::participant::*;
 use socialx::participant_set::*;
+use socialx::preset::{self, *};
+use socialx::report::*;
+use socialx::rng;
 use socialx::group::*;
+use socialx::itinerary::*;
 use socialx::round::*;
+use socialx::sat::*;
+use socialx::schedule::*;
+use socialx::solver::*;
+use socialx::tabu::*;
+use socialx::verify::*;
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+use std::thread;
+use std::time::Duration;
 
 fn setup(n_parts    : usize, 
          n_groups   : usize, 
@@ -48,11 +73,12 @@ fn participant_hget() {
 #[test]
 fn participant_group() {
     let (mut p, mut g) = setup(10, 5, 2);
+    let mut r = Rounds::new();
     let hg = g.hget(0);
     let hp = p.hget(0);
     assert!(p.group(hp) == HGROUP_NULL);
     
-    assert!(p.try_join(hp, hg, &mut g) == true);
+    assert!(p.try_join(hp, hg, &mut r, &mut g) == true);
     
     assert!(p.group(hp) == hg);
 }
@@ -63,7 +89,7 @@ fn participant_try_join() {
     let hr = r.hget(0);
     r.add_groups(hr, &hgs);
     for hp in p.iter() {
-        assert!(p.try_join_groups(hp, hr, &r, &mut g) == true);
+        assert!(p.try_join_groups(hp, hr, &mut r, &mut g) == true);
         assert!(p.group(hp) != HGROUP_NULL);
     }
 }
@@ -71,12 +97,13 @@ fn participant_try_join() {
 #[test]
 fn participant_leave_group() {
     let (mut p, mut g) = setup(10, 5, 2);
+    let mut r = Rounds::new();
     let hp = p.hget(0);
     let hg = g.hget(0);
     
-    assert!(p.try_join(hp, hg, &mut g) == true);
+    assert!(p.try_join(hp, hg, &mut r, &mut g) == true);
     assert!(p.group(hp) == hg);
-    p.leave_group(hp, hg, &mut g);
+    p.leave_group(hp, hg, &mut r, &mut g);
     assert!(p.group(hp) == HGROUP_NULL);
 }
 
@@ -88,8 +115,8 @@ fn participant_try_regroup() {
     let hp = p.hget(0);
     let hg = g.hget(0);
     
-    p.try_join(hp, hg, &mut g);
-    match p.try_regroup(hp, hr, &r, &mut g) {
+    p.try_join(hp, hg, &mut r, &mut g);
+    match p.try_regroup(hp, hr, &mut r, &mut g) {
         Ok(hp2) => {
             assert!(hp2 == HPARTICIPANT_NULL);
             assert!(p.group(hp) != hg);
@@ -108,12 +135,12 @@ fn participant_try_regroup_when_groups_full() {
     r.add_groups(hr, &hgs);
     // Load up groups.
     for hp in p.iter() {
-        p.try_join_groups(hp, hr, &r, &mut g);
+        p.try_join_groups(hp, hr, &mut r, &mut g);
     }
     // Get each participant to regroup.
     for hp in p.iter() {
         let hg = p.group(hp);
-        match p.try_regroup(hp, hr, &r, &mut g) {
+        match p.try_regroup(hp, hr, &mut r, &mut g) {
             Ok(hp2) => {
                 assert!(hp2 != HPARTICIPANT_NULL);
                 assert!(p.group(hp2) == hg);
@@ -127,6 +154,23 @@ fn participant_try_regroup_when_groups_full() {
     }
 }
 
+#[test]
+fn rounds_participant_group() {
+    let (mut p, mut g, mut r, hps, hgs, hrs) = setup2(4, 4, 2, 2);
+    r.add_groups(hrs[0], &hgs[..2]);
+    r.add_groups(hrs[1], &hgs[2..]);
+    assert!(p.try_join(hps[0], hgs[0], &mut r, &mut g));
+    assert!(p.try_join(hps[1], hgs[3], &mut r, &mut g));
+    assert!(r.participant_group(hrs[0], hps[0]) == hgs[0]);
+    assert!(r.participant_group(hrs[1], hps[1]) == hgs[3]);
+    assert!(r.participant_group(hrs[1], hps[0]) == HGROUP_NULL);
+    assert!(r.participant_group(hrs[0], hps[2]) == HGROUP_NULL);
+    p.leave_group(hps[0], hgs[0], &mut r, &mut g);
+    assert!(r.participant_group(hrs[0], hps[0]) == HGROUP_NULL);
+    r.reset();
+    assert!(r.participant_group(hrs[1], hps[1]) == HGROUP_NULL);
+}
+
 #[test]
 fn participant_set_has() {
     let (p, _g)  = setup(10, 5, 2);
@@ -140,24 +184,622 @@ fn participant_set_has() {
     assert!(pset.has(hp5) == true);
 }
 
+fn problem(n_parts    : usize,
+           n_groups   : usize,
+           n_rounds   : usize  ) -> Problem {
+    Problem { num_participants : n_parts,
+              num_groups       : n_groups,
+              num_rounds       : n_rounds,
+              num_attempts     : 10_000 }
+}
 
+#[test]
+fn schedule_display() {
+    let s = Schedule::new(4, 2, vec![vec![vec![0, 1], vec![2, 3]],
+                                     vec![vec![0, 2], vec![1, 3]]]);
+    assert_eq!(s.to_string(), "Round_1:\n    Group_1 : [ 1,  2]\n    \
+                               Group_2 : [ 3,  4]\n\n\
+                               Round_2:\n    Group_3 : [ 1,  3]\n    \
+                               Group_4 : [ 2,  4]\n");
+    assert!(s.num_placements() == 8);
+}
 
+#[test]
+fn problem_validate() {
+    assert!(problem(15,  5, 7).validate().is_ok());
+    assert!(problem(16,  5, 7).validate().is_err());
+    assert!(problem(15,  0, 7).validate().is_err());
+    assert!(problem(130, 10, 2).validate().is_err());
+    assert!(problem(7,   3, 7).validate().is_ok());
+    assert!(problem(7,   2, 7).validate().is_err());
+}
 
+#[test]
+fn solver_solve() {
+    let mut solver = Solver::new(problem(9, 3, 4));
+    let     result = solver.solve();
+    assert!(result.solved);
+    assert!(result.best.num_rounds() == 4);
+    assert!(result.best.num_placements() == 36);
+}
 
+#[test]
+fn solver_most_constrained() {
+    let mut p      = problem(15, 5, 7);
+    p.num_attempts = 1_000_000;
+    let mut solver = Solver::new(p);
+    solver.set_placement(Placement::MostConstrained);
+    let result = solver.solve();
+    assert!(result.solved);
+    assert!(verify(&result.best).is_empty());
+    assert!(solver.stats().attempts == result.attempts);
+    let stats = result.stats.unwrap();
+    assert!(stats == solver.stats());
+    assert!(stats.moved + stats.swapped + stats.failed == stats.regroups);
+    assert!(stats.restarts < stats.attempts);
+    assert!(stats.restarts_by_round.iter().sum::<usize>() == stats.restarts);
+    assert!(stats.deepest == 7);
+    assert!("constrained".parse::<Placement>()
+            == Ok(Placement::MostConstrained));
+    assert!("sorted".parse::<Placement>().is_err());
+}
 
+#[test]
+fn solver_partial_restart() {
+    let mut p      = problem(15, 5, 7);
+    p.num_attempts = 1_000_000;
+    let mut solver = Solver::new(p);
+    solver.set_restart(Restart::Partial);
+    let result = solver.solve();
+    assert!(result.solved);
+    assert!(verify(&result.best).is_empty());
+    assert!(solver.stats().partial < result.attempts);
+    assert!("partial".parse::<Restart>() == Ok(Restart::Partial));
+    assert!("never".parse::<Restart>().is_err());
+}
+
+#[test]
+fn solver_conflict_regroups() {
+    let mut solver = Solver::new(problem(32, 8, 7));
+    solver.set_regroup_choice(RegroupChoice::Conflict);
+    let result = solver.solve();
+    assert!(result.solved);
+    assert!(verify(&result.best).is_empty());
+    assert!("conflict".parse::<RegroupChoice>()
+            == Ok(RegroupChoice::Conflict));
+    assert!("nearest".parse::<RegroupChoice>().is_err());
+}
 
+#[test]
+fn solver_solutions_are_distinct() {
+    // The 9 participant, 4 round problem has a single solution up to
+    // relabeling, so only one should be found.
+    let mut p = problem(9, 3, 4);
+    p.num_attempts = 300;
+    let solutions = Solver::new(p).solutions().take(2).collect::<Vec<_>>();
+    assert!(solutions.len() == 1);
+}
 
+#[test]
+fn canonical_ignores_order_and_labels() {
+    let mut solver = Solver::new(problem(12, 4, 3));
+    let     s1     = solver.solve().best;
+
+    // Relabel the participants, then reverse the rounds and their groups.
+    let labels = [5, 11, 0, 7, 2, 9, 4, 1, 10, 3, 8, 6];
+    let rounds = s1.rounds().iter().rev().map(|round| {
+        round.iter().rev().map(|group| {
+            group.iter().map(|&p| labels[p]).collect()
+        }).collect()
+    }).collect();
+    let s2 = Schedule::new(12, 3, rounds);
+
+    assert!(s1 != s2);
+    assert!(s1.is_isomorphic(&s2));
+    assert!(s1.canonical() == s2.canonical());
+    assert!(s1.canonical().to_schedule().canonical() == s1.canonical());
+
+    let mut set = HashSet::new();
+    set.insert(s1.canonical());
+    assert!(!set.insert(CanonicalSchedule::new(&s2)));
+}
 
+#[test]
+fn canonical_distinguishes_schedules() {
+    // The second round of the first schedule pairs participants that sat
+    // apart in the first round, the second schedule pairs some that met.
+    let s1 = Schedule::new(6, 3, vec![vec![vec![0, 1, 2], vec![3, 4, 5]],
+                                      vec![vec![0, 3], vec![1, 4], 
+                                           vec![2, 5]]]);
+    let s2 = Schedule::new(6, 3, vec![vec![vec![0, 1, 2], vec![3, 4, 5]],
+                                      vec![vec![0, 1], vec![3, 4], 
+                                           vec![2, 5]]]);
+    assert!(!s1.is_isomorphic(&s2));
+    assert!(s1.canonical() != s2.canonical());
+}
 
+#[test]
+fn schedule_parse() {
+    let mut solver = Solver::new(problem(12, 4, 3));
+    let     s1     = solver.solve().best;
+    let     text   = format!("Best so far:  36 placements out of  36.\n\n{}",
+                             s1);
+    let     s2     = text.parse::<Schedule>().unwrap();
+    assert!(s1 == s2);
+    assert!("Group_1 : [ 1,  2]".parse::<Schedule>().is_err());
+    assert!("Round_1:\n    Group_1 : [ 1,  x]".parse::<Schedule>().is_err());
+}
 
+#[test]
+fn verify_solution() {
+    let mut p = problem(15, 5, 7);
+    p.num_attempts = 1_000_000;
+    let mut solver = Solver::new(p);
+    let     result = solver.solve();
+    assert!(result.solved);
+    assert!(verify(&result.best).is_empty());
+}
 
+#[test]
+fn verify_violations() {
+    use Violation::*;
+    let s = Schedule::new(6, 3, vec![vec![vec![0, 1, 2], vec![3, 4, 5]],
+                                     vec![vec![0, 1, 3, 6], vec![4, 4]]]);
+    let v = verify(&s);
+    assert!(v.contains(&OverCapacity  { round: 2, group: 3, size: 4, 
+                                        capacity: 3 }));
+    assert!(v.contains(&UnderCapacity { round: 2, group: 4, size: 2, 
+                                        capacity: 3 }));
+    assert!(v.contains(&Unknown       { round: 2, group: 3, 
+                                        participant: 6 }));
+    assert!(v.contains(&PlacedTwice   { round: 2, participant: 4, 
+                                        groups: vec![4, 4] }));
+    assert!(v.contains(&Missing       { round: 2, participant: 2 }));
+    assert!(v.contains(&Missing       { round: 2, participant: 5 }));
+    assert!(v.contains(&RepeatedPair  { first: 0, second: 1, 
+                                        rounds: vec![1, 2] }));
+    assert!(v.len() == 7);
+
+    // Groups are named by their labels in the printed schedule.
+    let over = v.iter().find(|x| matches!(x, OverCapacity { .. })).unwrap();
+    assert!(s.to_string().contains("Group_3 : [ 1,  2,  4,  7]"));
+    assert!(over.to_string() == "Round_2: Group_3 has 4 members, more than \
+                                 its capacity of 3.");
+}
 
+#[test]
+fn anneal_solve() {
+    let mut annealer = Annealer::new(problem(15, 5, 7));
+    let     result   = annealer.solve();
+    assert!(result.solved);
+    assert!(result.best.num_placements() == 105);
+    assert!(verify(&result.best).is_empty());
+}
 
+#[test]
+fn anneal_trivial() {
+    // The first deal of a single round is already a solution.
+    let result = Annealer::new(problem(15, 5, 1)).solve();
+    assert!(result.solved && result.attempts == 1);
+    assert!(result.best.num_placements() == 15);
+    assert!(verify(&result.best).is_empty());
+
+    // With a single group there's no swap to make.
+    let result = Annealer::new(problem(4, 1, 2)).solve();
+    assert!(!result.solved);
+    assert!(result.best.num_placements() >= 4);
+}
 
+#[test]
+fn tabu_solve() {
+    let mut search = TabuSearch::new(problem(15, 5, 7));
+    let     result = search.solve();
+    assert!(result.solved);
+    assert!(verify(&result.best).is_empty());
+}
 
+#[test]
+fn tabu_trivial() {
+    let result = TabuSearch::new(problem(15, 5, 1)).solve();
+    assert!(result.solved && result.attempts == 1);
+    assert!(result.best.num_placements() == 15);
+    assert!(verify(&result.best).is_empty());
+
+    let result = TabuSearch::new(problem(4, 1, 2)).solve();
+    assert!(!result.solved && result.attempts == 1);
+}
 
+#[test]
+fn bye_not_searched() {
+    // Only the round robin construction schedules a bye.
+    let p = problem(7, 3, 3);
+    assert!(p.has_bye() && p.validate().is_ok());
+    let result = Annealer::new(p).solve();
+    assert!(!result.solved && result.attempts == 0);
+    let result = TabuSearch::new(p).solve();
+    assert!(!result.solved && result.attempts == 0);
+    let result = ExactSearch::new(p).solve();
+    assert!(!result.solved && !result.exhausted && result.nodes == 0);
+}
 
+#[test]
+fn exact_solve() {
+    let mut search = ExactSearch::new(problem(9, 3, 4));
+    let     result = search.solve();
+    assert!(result.solved && result.exhausted);
+    assert!(result.nodes > 0);
+    assert!(verify(&result.best).is_empty());
+}
 
+#[test]
+fn exact_infeasible() {
+    // Two groups of three can't be regrouped without a repeat.
+    let result = ExactSearch::new(problem(6, 2, 2)).solve();
+    assert!(result.infeasible());
+    assert!(result.nodes > 0);
+
+    // Too many rounds for everyone to keep meeting new people.
+    let result = ExactSearch::new(problem(9, 3, 5)).solve();
+    assert!(result.infeasible());
+
+    // Out of nodes before an answer.
+    let mut p = problem(15, 5, 7);
+    p.num_attempts = 100;
+    let result = ExactSearch::new(p).solve();
+    assert!(!result.solved && !result.exhausted);
+    assert!(result.nodes == 100);
+}
 
+#[test]
+fn algorithm_from_str() {
+    assert!("regroup".parse::<Algorithm>() == Ok(Algorithm::Regroup));
+    assert!("anneal" .parse::<Algorithm>() == Ok(Algorithm::Anneal));
+    assert!("tabu"   .parse::<Algorithm>() == Ok(Algorithm::Tabu));
+    assert!("exact"  .parse::<Algorithm>() == Ok(Algorithm::Exact));
+    assert!("magic"  .parse::<Algorithm>().is_err());
+}
 
+#[test]
+fn sat_round_trip() {
+    let mut solver  = Solver::new(problem(9, 3, 4));
+    let     result  = solver.solve();
+    let     encoder = SatEncoder::new(problem(9, 3, 4));
+    assert!(result.solved);
+
+    // The model of a known schedule decodes back into it.
+    let mut model = String::from("s SATISFIABLE\nv");
+    for (r, round) in result.best.rounds().iter().enumerate() {
+        for (g, group) in round.iter().enumerate() {
+            for &p in group {
+                model.push_str(&format!(" {}", encoder.var(p, r, g)));
+            }
+        }
+    }
+    model.push_str(" 0\n");
+    let schedule = encoder.decode(&model).unwrap();
+    assert!(schedule == result.best);
+    assert!(verify(&schedule).is_empty());
 
+    assert!(encoder.decode("s UNSATISFIABLE\n").is_err());
+    assert!(encoder.decode("").is_err());
+}
+
+#[test]
+fn sat_encode() {
+    let mut encoder = SatEncoder::new(problem(9, 3, 4));
+    let     plain   = encoder.encode();
+    assert!(plain.num_vars > 9 * 4 * 3);
+    assert!(plain.to_string().contains(&format!("p cnf {} {}\n",
+                                                plain.num_vars,
+                                                plain.clauses.len())));
+
+    // The first round and the first group's members are fixed.
+    encoder.symmetry = true;
+    let fixed = encoder.encode();
+    assert!(fixed.clauses.len() == plain.clauses.len() + 9 + 3 * 3);
+
+    // Pins become unit clauses and turn off symmetry breaking.
+    encoder.pins = vec![(4, 1, 2)];
+    let pinned = encoder.encode();
+    assert!(pinned.clauses.len() == plain.clauses.len() + 1);
+    assert!(pinned.clauses.contains(&vec![encoder.var(4, 1, 2)]));
+
+    encoder.pins = vec![(9, 0, 0)];
+    assert!(encoder.validate().is_err());
+}
+
+#[test]
+fn construct_designs() {
+    let cases = [(9,  3,  4,  Design::AffineGeometry { order: 3, dimension: 2 }),
+                 (27, 9,  13, Design::AffineGeometry { order: 3, dimension: 3 }),
+                 (16, 4,  3,  Design::AffineGeometry { order: 4, dimension: 2 }),
+                 (15, 5,  7,  Design::Kirkman { order: 15 }),
+                 (45, 15, 22, Design::Kirkman { order: 45 }),
+                 (21, 7,  10, Design::Kirkman { order: 21 }),
+                 (33, 11, 16, Design::Kirkman { order: 33 }),
+                 (28, 7,  9,  Design::Difference { modulus: 9, 
+                                                   group_size: 4 })];
+    for &(n_parts, n_groups, n_rounds, design) in &cases {
+        let construction = Construction::new(problem(n_parts, n_groups,
+                                                     n_rounds)).unwrap();
+        let schedule     = construction.schedule();
+        assert!(construction.design() == design);
+        assert!(schedule.num_rounds() == n_rounds);
+        assert!(verify(&schedule).is_empty());
+    }
+    // Pairs, with a bye for an odd number of participants.
+    for &(n_parts, n_groups) in &[(8, 4), (7, 3)] {
+        let construction = Construction::new(problem(n_parts, n_groups, 
+                                                     7)).unwrap();
+        let schedule     = construction.schedule();
+        assert!(construction.design() 
+                    == Design::RoundRobin { participants: n_parts });
+        assert!(verify(&schedule).is_empty());
+        assert!(schedule.to_string().parse::<Schedule>() == Ok(schedule));
+    }
+    assert!(Construction::new(problem(8, 4, 8)).is_none());
+
+    // Too many rounds, and parameters no design covers.
+    assert!(Construction::new(problem(9, 3, 5)).is_none());
+    assert!(Construction::new(problem(32, 8, 8)).is_none());
+}
+
+#[test]
+fn mols_orthogonal() {
+    for order in 2..=12 {
+        let mols = Mols::new(order);
+        let sqs  = mols.squares();
+        for (i, a) in sqs.iter().enumerate() {
+            for b in &sqs[i + 1..] {
+                assert!(is_orthogonal(a, b));
+            }
+        }
+        let schedule = mols.schedule(mols.num_rounds()).unwrap();
+        assert!(verify(&schedule).is_empty());
+    }
+    assert!(Mols::new(7) .squares().len() == 6);
+    assert!(Mols::new(6) .squares().len() == 1);
+    assert!(Mols::new(10).squares().len() == 2);
+    assert!(Mols::new(12).squares().len() == 2);
+    assert!(Mols::new(10).schedule(5).is_none());
+
+    let construction = Construction::new(problem(100, 10, 4)).unwrap();
+    assert!(construction.design() 
+                == Design::LatinSquares { order: 10, squares: 2 });
+}
+
+#[test]
+fn solver_with_start() {
+    let start  = Construction::new(problem(15, 5, 4)).unwrap().schedule();
+    let mut p  = problem(15, 5, 7);
+    p.num_attempts = 1_000_000;
+    let result = Solver::with_start(p, &start).solve();
+    assert!(result.solved);
+    assert!(result.best.rounds()[..4] == *start.rounds());
+    assert!(verify(&result.best).is_empty());
+}
+
+#[test]
+fn seeded_runs_repeat() {
+    let solve = |seed| {
+        rng::seed(seed);
+        Solver::new(problem(12, 4, 3)).solve().best
+    };
+    assert!(solve(7) == solve(7));
+}
+
+#[test]
+fn bench_run() {
+    let nine   = CATALOG.iter().find(|inst| inst.name == "nine").unwrap();
+    let result = bench::run(nine, 4, 1, |p| Solver::new(p).solve());
+    assert!(result.runs == 4);
+    assert!(result.solved() == 4);
+    assert!(result.success_rate() == 1.0);
+    assert!(result.median() <= result.p95());
+    assert!(result.p95() == result.times.last().copied());
+
+    let result = bench::run(nine, 3, 1, |p| {
+        SolveResult { solved: false, attempts: 0, stats: None,
+                      best: Schedule::new(p.num_participants, p.group_size(),
+                                          vec![]) }
+    });
+    assert!(result.success_rate() == 0.0);
+    assert!(result.median().is_none());
+}
+
+#[test]
+fn presets() {
+    for preset in &PRESETS {
+        assert!(preset.problem(1).validate().is_ok());
+        assert!(preset::find(preset.name).unwrap().name == preset.name);
+    }
+    // The presets that name a design are built from it in full.
+    for name in &["nine", "kirkman", "sixteen", "kts21", "kts27", "chess8",
+                  "squares100"] {
+        let preset       = preset::find(name).unwrap();
+        let construction = Construction::new(preset.problem(1)).unwrap();
+        assert!(construction.schedule().num_rounds() == preset.best_known);
+    }
+    assert!(preset::find("kirkwood").is_none());
+    assert!(preset::find("sylvester").is_none());
+    assert!(preset::unmodeled("sylvester").is_some());
+    assert!(preset::unmodeled("kirkman").is_none());
+}
+
+#[test]
+fn checkpoint_resume() {
+    // Take a checkpoint after every attempt, and resume from one taken
+    // partway through.
+    let run = |solver: &mut Solver| {
+        let taken = Rc::new(RefCell::new(vec![]));
+        let sink  = taken.clone();
+        solver.set_checkpoints(Duration::ZERO,
+                               move |cp: &Checkpoint| {
+                                   sink.borrow_mut().push(cp.clone())
+                               });
+        let result = solver.solve();
+        let taken  = taken.borrow().clone();
+        (result, taken)
+    };
+    let mut p = problem(15, 5, 7);
+    p.num_attempts = 200;
+    rng::seed(3);
+    let mut solver = Solver::new(p);
+    solver.set_restart(Restart::Partial);
+    let (result, taken) = run(&mut solver);
+    assert!(taken.len() == result.attempts - result.solved as usize);
+
+    let cp = taken.iter().find(|cp| cp.attempts >= 50
+                                    && cp.kept.num_rounds() > 1).unwrap();
+    let cp = cp.to_string().parse::<Checkpoint>().unwrap();
+    assert!(&cp == taken.iter().find(|t| t.attempts == cp.attempts).unwrap());
+
+    let mut resumed = Solver::from_checkpoint(&cp);
+    let (resumed_result, resumed_taken) = run(&mut resumed);
+    assert!(resumed_result.best == result.best);
+    assert!(resumed_result.solved == result.solved);
+    assert!(resumed_result.attempts == result.attempts);
+    assert!(resumed_taken[..] == taken[cp.attempts..]);
+
+    assert!("socialx".parse::<Checkpoint>().is_err());
+}
+
+#[test]
+fn cancel_search() {
+    let cancel = CancelToken::new();
+    cancel.clone().cancel();
+    for &algorithm in &[Algorithm::Regroup, Algorithm::Anneal,
+                        Algorithm::Tabu, Algorithm::Exact] {
+        let result = algorithm.solve_until(problem(15, 5, 7), &cancel, |_| {});
+        assert!(result.solved == false);
+        assert!(result.attempts == 0);
+    }
+    // Cancel from another thread a search that can't succeed.
+    let mut p = problem(15, 5, 8);
+    p.num_attempts = usize::MAX;
+    let cancel = CancelToken::new();
+    let token  = cancel.clone();
+    let mut solver = Solver::new(p);
+    solver.set_cancel(cancel);
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        token.cancel();
+    });
+    let result = solver.solve();
+    assert!(result.solved == false);
+    assert!(result.attempts > 0);
+    assert!(result.best.num_placements() > 0);
+}
+
+#[test]
+fn report_json() {
+    let schedule = Schedule::new(4, 2, vec![vec![vec![0, 1], vec![2, 3]],
+                                            vec![vec![0, 2], vec![1, 3]]]);
+    let names    = vec!["Ann \"A\"".to_string()];
+    let mut report = Report::new(problem(4, 2, 2), &schedule, Status::Solved,
+                                 "regroup");
+    report.names    = &names;
+    report.attempts = Some(3);
+    let json = report.to_json();
+    assert!(json.contains("\"version\": 1,"));
+    assert!(json.contains("\"status\": \"solved\","));
+    assert!(json.contains("\"design\": null,"));
+    assert!(json.contains("\"placements\": 8,"));
+    assert!(json.contains("\"attempts\": 3,"));
+    assert!(json.contains("\"stats\": null"));
+    assert!(json.contains("{\"group\": 1, \"members\": \
+                           [{\"id\": 1, \"name\": \"Ann \\\"A\\\"\"}, \
+                           {\"id\": 2, \"name\": null}]},"));
+    assert!(json.contains("{\"group\": 4, \"members\": \
+                           [{\"id\": 2, \"name\": null}, \
+                           {\"id\": 4, \"name\": null}]}\n"));
+    assert!(json.matches('{').count() == json.matches('}').count());
+}
+
+#[test]
+fn report_csv() {
+    let schedule = Schedule::new(5, 2, vec![vec![vec![0, 1], vec![2, 3]],
+                                            vec![vec![0, 2], vec![1, 4]]]);
+    let names    = vec!["Ann, A".to_string(), "Bo".to_string()];
+    let mut report = Report::new(problem(5, 2, 2), &schedule, Status::Solved,
+                                 "construction");
+    report.names = &names;
+    assert!(report.to_csv() == "round,group,participant,name\n\
+                                1,Group_1,1,\"Ann, A\"\n\
+                                1,Group_1,2,Bo\n\
+                                1,Group_2,3,\n\
+                                1,Group_2,4,\n\
+                                1,Bye,5,\n\
+                                2,Group_3,1,\"Ann, A\"\n\
+                                2,Group_3,3,\n\
+                                2,Group_4,2,Bo\n\
+                                2,Group_4,5,\n\
+                                2,Bye,4,\n");
+    assert!(report.to_csv_wide() == "participant,name,Round_1,Round_2\n\
+                                     1,\"Ann, A\",Group_1,Group_3\n\
+                                     2,Bo,Group_1,Group_4\n\
+                                     3,,Group_2,Group_3\n\
+                                     4,,Group_2,Bye\n\
+                                     5,,Bye,Group_4\n");
+}
+
+#[test]
+fn itineraries() {
+    let schedule = Schedule::new(5, 2, vec![vec![vec![0, 1], vec![2, 3]],
+                                            vec![vec![0, 2], vec![1, 4]]]);
+    let all = Itinerary::all(&schedule);
+    assert!(all.len() == 5);
+    assert!(all[0].stops == vec![Stop { group: Some(1), mates: vec![1] },
+                                 Stop { group: Some(3), mates: vec![2] }]);
+    assert!(all[4].stops[0] == Stop { group: None, mates: vec![] });
+
+    let names  = vec!["Ann".to_string()];
+    let mut report = Report::new(problem(5, 2, 2), &schedule, Status::Solved,
+                                 "regroup");
+    report.names = &names;
+    let text = report.itinerary_text();
+    assert!(text.starts_with("Participant 1 (Ann):\n    \
+                              Round_1 : Group_1 with 2\n    \
+                              Round_2 : Group_3 with 3\n\n\
+                              Participant 2:\n    \
+                              Round_1 : Group_1 with Ann\n"));
+    assert!(text.ends_with("Participant 5:\n    \
+                            Round_1 : Bye\n    \
+                            Round_2 : Group_4 with 2\n"));
+    assert!(report.itinerary_csv().contains("\n2,,1,Group_1,1,Ann\n"));
+    assert!(report.itinerary_json().contains("{\"round\": 1, \"group\": null, \
+                                              \"label\": \"Bye\", \
+                                              \"bye\": true, \"mates\": []}"));
+}
+
+#[test]
+fn meetings() {
+    let schedule = Schedule::new(4, 2, vec![vec![vec![0, 1], vec![2, 3]],
+                                            vec![vec![0, 2], vec![1, 3]]]);
+    let meetings = Meetings::new(&schedule);
+    assert!(meetings.round(0, 1) == Some(0));
+    assert!(meetings.round(3, 1) == Some(1));
+    assert!(meetings.round(0, 3).is_none());
+    assert!(meetings.acquaintances(0).count() == 2);
+    assert!(meetings.never_met() == vec![(0, 3), (1, 2)]);
+
+    let names  = vec!["Ann".to_string()];
+    let mut report = Report::new(problem(4, 2, 2), &schedule, Status::Solved,
+                                 "regroup");
+    report.names = &names;
+    assert!(report.meetings_text() == "  1 2 3 4\n\
+                                       1 - 1 2\n\
+                                       2 1 -   2\n\
+                                       3 2   - 1\n\
+                                       4   2 1 -\n\
+                                       \n\
+                                       Pairs that never met: 2\n    \
+                                       Ann and 4\n    \
+                                       2 and 3\n");
+    assert!(report.meetings_csv() == "participant,name,1,2,3,4\n\
+                                      1,Ann,,1,2,\n\
+                                      2,,1,,,2\n\
+                                      3,,2,,,1\n\
+                                      4,,,2,1,\n");
+    assert!(report.meetings_json().contains("\"never_met\": [[1, 4], [2, 3]]"));
+}